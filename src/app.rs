@@ -1,21 +1,45 @@
-use std::{cell::RefCell, io, rc::Rc, time::Duration};
+use std::{
+    cell::RefCell,
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    process::Command,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
 
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use notify_rust::Notification;
 use ratatui::{
     DefaultTerminal, Frame,
-    crossterm::event::{self, Event, KeyCode, KeyEventKind, poll},
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind, poll},
+        execute,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    },
     layout::Rect,
+    text::Line,
     widgets::{Block, Borders},
 };
+use uuid::Uuid;
 
 use crate::{
+    formats::csv::export_csv,
     popup_factory::PopupFactory,
-    popups::task_list::Task,
+    popups::task_list::{Task, TaskStatus},
+    recurrence::Recurrence,
+    sessions::{Session, SessionFileError, SessionManager},
+    settings::SettingsManager,
     storage::{SaveTaskError, TaskManager},
     theme::Theme,
 };
 use crate::{
-    screens::{countdown::CountdownScreen, welcome::WelcomeScreen},
-    utils::{DEFAULT_BREAK_DURATION_MINUTES, DEFAULT_WORK_DURATION_MINUTES},
+    screens::{board::BoardScreen, countdown::CountdownScreen, welcome::WelcomeScreen},
+    utils::{CountdownType, DEFAULT_BREAK_DURATION_MINUTES, DEFAULT_WORK_DURATION_MINUTES},
 };
 
 pub enum Action {
@@ -25,7 +49,63 @@ pub enum Action {
         break_duration_minutes: u64,
     },
     AddTask {
-        task: Task,
+        task: Box<Task>,
+    },
+    EditTask {
+        task_uuid: Uuid,
+        title: String,
+        status: TaskStatus,
+    },
+    SetTasksStatus {
+        task_uuids: Vec<Uuid>,
+        status: TaskStatus,
+    },
+    TagTasks {
+        task_uuids: Vec<Uuid>,
+        tag: String,
+    },
+    SetTaskNotes {
+        task_uuid: Uuid,
+        notes: String,
+        task_opened_on_idx: usize,
+    },
+    /// Hands the terminal over to `$EDITOR` to edit the notes of a task
+    EditTaskNotesInEditor {
+        task_uuid: Uuid,
+        task_opened_on_idx: usize,
+    },
+    /// Sets the rule typed by the user, or makes the tasks one-off when it is empty
+    SetTasksRecurrence {
+        task_uuids: Vec<Uuid>,
+        recurrence: String,
+    },
+    TasksCompleted {
+        task_uuids: Vec<Uuid>,
+    },
+    /// Shows another list, `None` being the default list
+    SelectTaskList {
+        list: Option<String>,
+    },
+    MoveTasksToList {
+        task_uuids: Vec<Uuid>,
+        list: Option<String>,
+    },
+    RecordSession {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        phase: CountdownType,
+        duration_seconds: u64,
+    },
+    ExportCsv {
+        directory: String,
+    },
+    /// Shows the tasks of the selected list on a board, one column per status
+    OpenBoard,
+    /// Goes back to the screen the board was opened from
+    CloseBoard,
+    /// Keeps our conflicting change over the stored tasks, or drops it
+    ResolveTaskConflict {
+        keep_ours: bool,
     },
 
     OpenPopup {
@@ -37,56 +117,201 @@ pub enum Action {
 pub trait Screen {
     fn draw(&self, frame: &mut Frame, area: Rect, theme: &Theme);
     fn handle_event(&mut self, event: &Event) -> Option<Action>;
-    fn update(&mut self);
+    fn update(&mut self) -> Option<Action>;
 }
 
 pub trait Popup {
     fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme);
     fn handle_event(&mut self, event: &Event) -> Option<Action>;
+
+    /// Whether the popup takes typed text, which global shortcuts such as `q` must not eat
+    fn is_typing(&self) -> bool {
+        false
+    }
 }
 
 pub struct App {
     popup_factory: Rc<PopupFactory>,
     current_screen: Box<dyn Screen>,
+    /// Screen left for the board, still updated so that a running countdown goes on
+    background_screen: Option<Box<dyn Screen>>,
     current_popup: Option<Box<dyn Popup>>,
     theme: Theme,
     work_duration_minutes: u64,
     break_duration_minutes: u64,
+    /// Shown on the border, such as when the tasks were opened read-only
+    banner: Option<&'static str>,
+    last_task_reload_check: Instant,
+    /// Task whose notes to edit in `$EDITOR` once the terminal is free, with the task list row
+    /// to go back to
+    notes_editor_request: Option<(Uuid, usize)>,
+    /// Day recurring tasks were last brought back on, to do it again after midnight
+    last_recurrence_reset_date: Option<NaiveDate>,
+    conflict_popup_open: bool,
     exit: bool,
 }
 
+/// How often the stored tasks are checked for changes made elsewhere
+const TASK_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
 impl App {
-    pub fn new() -> Self {
+    pub fn new(
+        mut task_manager: TaskManager,
+        session_manager: SessionManager,
+        settings_manager: SettingsManager,
+        banner: Option<&'static str>,
+    ) -> Self {
+        task_manager.selected_list = settings_manager.settings.selected_task_list.clone();
+
         App {
-            // TODO: Handle errors
-            popup_factory: Rc::new(PopupFactory::new(Rc::new(RefCell::new(
-                TaskManager::new("./tasks.json").unwrap(),
-            )))),
+            popup_factory: Rc::new(PopupFactory::new(
+                Rc::new(RefCell::new(task_manager)),
+                Rc::new(RefCell::new(settings_manager)),
+                Rc::new(RefCell::new(session_manager)),
+            )),
             current_screen: Box::new(WelcomeScreen::new()),
+            background_screen: None,
             current_popup: None,
             theme: Theme::catppuccin_mocha(),
             work_duration_minutes: DEFAULT_WORK_DURATION_MINUTES,
             break_duration_minutes: DEFAULT_BREAK_DURATION_MINUTES,
+            banner,
+            last_task_reload_check: Instant::now(),
+            last_recurrence_reset_date: None,
+            notes_editor_request: None,
+            conflict_popup_open: false,
             exit: false,
         }
     }
 
+    /// Opens an error popup over the first screen, for problems found before the app started
+    pub fn show_error(&mut self, error_content: String) {
+        self.handle_action(Action::OpenPopup {
+            popup: self.popup_factory.create_error_popup(error_content),
+        });
+    }
+
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
         while !self.exit {
             terminal.draw(|frame| self.draw(frame))?;
-            self.current_screen.update();
+            if let Some(action) = self.current_screen.update() {
+                self.handle_action(action);
+            }
+            if let Some(action) = self
+                .background_screen
+                .as_mut()
+                .and_then(|background_screen| background_screen.update())
+            {
+                self.handle_action(action);
+            }
             if poll(Duration::from_millis(10))? {
                 self.handle_event()?;
             }
+            if let Some((task_uuid, task_opened_on_idx)) = self.notes_editor_request.take() {
+                self.edit_notes_in_editor(terminal, task_uuid, task_opened_on_idx)?;
+            }
+            self.sync_tasks();
         }
         Ok(())
     }
 
+    /// Suspends the TUI while `$EDITOR` edits the notes of a task in a temporary file, then saves
+    /// them
+    fn edit_notes_in_editor(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        task_uuid: Uuid,
+        task_opened_on_idx: usize,
+    ) -> io::Result<()> {
+        let Some(notes) = self
+            .popup_factory
+            .task_manager
+            .borrow()
+            .tasks
+            .iter()
+            .find(|task| task.uuid == task_uuid)
+            .map(|task| task.notes.clone())
+        else {
+            return Ok(());
+        };
+
+        disable_raw_mode()?;
+        execute!(io::stdout(), LeaveAlternateScreen)?;
+        let edit_result = edit_in_editor(&notes, "focus-tui-notes");
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        enable_raw_mode()?;
+        terminal.clear()?;
+
+        match edit_result {
+            Ok(edited_notes) => self.handle_action(Action::SetTaskNotes {
+                task_uuid,
+                notes: edited_notes,
+                task_opened_on_idx,
+            }),
+            Err(error) => self.handle_action(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error),
+            }),
+        }
+        Ok(())
+    }
+
+    /// Picks up changes made to the stored tasks elsewhere, and asks what to do with a change
+    /// that conflicts with them
+    fn sync_tasks(&mut self) {
+        if self.popup_factory.task_manager.borrow().has_conflict() {
+            // Also reopened once an error about resolving the conflict is dismissed
+            if !self.conflict_popup_open || self.current_popup.is_none() {
+                self.conflict_popup_open = true;
+                self.handle_action(Action::OpenPopup {
+                    popup: self.popup_factory.create_conflict_popup(),
+                });
+            }
+            return;
+        }
+        if self.last_task_reload_check.elapsed() < TASK_RELOAD_INTERVAL {
+            return;
+        }
+        self.last_task_reload_check = Instant::now();
+
+        let reload_result = self
+            .popup_factory
+            .task_manager
+            .borrow_mut()
+            .reload_if_changed();
+        if let Err(error) = reload_result {
+            self.handle_action(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error.to_string()),
+            });
+        }
+
+        let today = Local::now().date_naive();
+        if self.last_recurrence_reset_date != Some(today) {
+            self.last_recurrence_reset_date = Some(today);
+            let reset_result = self
+                .popup_factory
+                .task_manager
+                .borrow_mut()
+                .reset_recurring_tasks();
+            match reset_result {
+                // Left to the instance that may save them
+                Ok(()) | Err(SaveTaskError::ReadOnly) => {}
+                Err(error) => self.handle_action(Action::OpenPopup {
+                    popup: self.popup_factory.create_error_popup(error.to_string()),
+                }),
+            }
+        }
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_style(self.theme.border_style)
             .style(self.theme.background_style);
+        if let Some(banner) = self.banner {
+            block = block.title_top(
+                Line::styled(format!(" {} ", banner), self.theme.error_text_style()).centered(),
+            );
+        }
         frame.render_widget(block.clone(), frame.area());
         let inner_area = block.inner(frame.area());
         self.current_screen.draw(frame, inner_area, &self.theme);
@@ -99,12 +324,24 @@ impl App {
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 match key_event.code {
-                    KeyCode::Char('q') => self.handle_action(Action::Quit),
+                    KeyCode::Char('q')
+                        if !self
+                            .current_popup
+                            .as_ref()
+                            .is_some_and(|current_popup| current_popup.is_typing()) =>
+                    {
+                        self.handle_action(Action::Quit)
+                    }
                     KeyCode::Char('t') if self.current_popup.is_none() => {
                         self.handle_action(Action::OpenPopup {
                             popup: self.popup_factory.create_task_list_popup(0),
                         })
                     }
+                    KeyCode::Char('b')
+                        if self.current_popup.is_none() && self.background_screen.is_none() =>
+                    {
+                        self.handle_action(Action::OpenBoard)
+                    }
                     _ => {
                         if let Some(action) = {
                             if let Some(current_popup) = &mut self.current_popup {
@@ -140,7 +377,7 @@ impl App {
             }
             Action::AddTask { task } => {
                 let add_result: Result<usize, SaveTaskError> =
-                    self.popup_factory.task_manager.borrow_mut().add_task(task);
+                    self.popup_factory.task_manager.borrow_mut().add_task(*task);
 
                 match add_result {
                     Ok(idx) => self.handle_action(Action::OpenPopup {
@@ -151,8 +388,305 @@ impl App {
                     }),
                 }
             }
+            Action::EditTask {
+                task_uuid,
+                title,
+                status,
+            } => {
+                let edit_result = self
+                    .popup_factory
+                    .task_manager
+                    .borrow_mut()
+                    .edit_task(task_uuid, title, status);
+                self.show_task_change_result(edit_result, Some(task_uuid));
+            }
+            Action::SetTasksStatus { task_uuids, status } => {
+                let set_status_result = self
+                    .popup_factory
+                    .task_manager
+                    .borrow_mut()
+                    .set_tasks_status(&task_uuids, status);
+                self.show_task_change_result(set_status_result, task_uuids.first().copied());
+            }
+            Action::TagTasks { task_uuids, tag } => {
+                let tag = tag.trim().trim_start_matches('#').to_string();
+                let tag_result = if tag.is_empty() {
+                    Ok(())
+                } else {
+                    self.popup_factory
+                        .task_manager
+                        .borrow_mut()
+                        .tag_tasks(&task_uuids, &tag)
+                };
+                self.show_task_change_result(
+                    tag_result.map(|_| Vec::new()),
+                    task_uuids.first().copied(),
+                );
+            }
+            Action::SetTaskNotes {
+                task_uuid,
+                notes,
+                task_opened_on_idx,
+            } => {
+                let notes_result = self
+                    .popup_factory
+                    .task_manager
+                    .borrow_mut()
+                    .set_task_notes(task_uuid, notes);
+                match notes_result {
+                    Ok(()) => self.handle_action(Action::OpenPopup {
+                        popup: self
+                            .popup_factory
+                            .create_task_detail_popup(task_uuid, task_opened_on_idx),
+                    }),
+                    Err(error) => self.handle_action(Action::OpenPopup {
+                        popup: self.popup_factory.create_error_popup(error.to_string()),
+                    }),
+                }
+            }
+            Action::EditTaskNotesInEditor {
+                task_uuid,
+                task_opened_on_idx,
+            } => self.notes_editor_request = Some((task_uuid, task_opened_on_idx)),
+            Action::SetTasksRecurrence {
+                task_uuids,
+                recurrence,
+            } => {
+                let recurrence = if recurrence.trim().is_empty() {
+                    None
+                } else {
+                    match Recurrence::parse(&recurrence) {
+                        Some(recurrence) => Some(recurrence),
+                        None => {
+                            return self.handle_action(Action::OpenPopup {
+                                popup: self.popup_factory.create_error_popup(format!(
+                                    "Unknown repeat rule '{}', try daily, weekdays, weekly mon thu or every 3 days",
+                                    recurrence.trim()
+                                )),
+                            });
+                        }
+                    }
+                };
+                let recurrence_result = self
+                    .popup_factory
+                    .task_manager
+                    .borrow_mut()
+                    .set_tasks_recurrence(&task_uuids, recurrence);
+                self.show_task_change_result(
+                    recurrence_result.map(|_| Vec::new()),
+                    task_uuids.first().copied(),
+                );
+            }
+            Action::OpenBoard => {
+                let board_screen = Box::new(BoardScreen::new(
+                    self.popup_factory.clone(),
+                    self.popup_factory.task_manager.clone(),
+                ));
+                self.background_screen =
+                    Some(std::mem::replace(&mut self.current_screen, board_screen));
+            }
+            Action::CloseBoard => {
+                if let Some(background_screen) = self.background_screen.take() {
+                    self.current_screen = background_screen;
+                }
+            }
+            Action::TasksCompleted { task_uuids } => {
+                let task_manager = self.popup_factory.task_manager.borrow();
+                for task in task_manager
+                    .tasks
+                    .iter()
+                    .filter(|task| task_uuids.contains(&task.uuid))
+                {
+                    let notification = Notification::new()
+                        .summary("Task completed")
+                        .body(&format!("Well done, \"{}\" is done!", task.title))
+                        .finalize();
+
+                    if let Err(e) = notification.show() {
+                        eprintln!("Failed to show notification: {}", e);
+                    }
+                }
+            }
+            Action::SelectTaskList { list } => {
+                self.popup_factory.task_manager.borrow_mut().selected_list = list.clone();
+                let save_result = self
+                    .popup_factory
+                    .settings_manager
+                    .borrow_mut()
+                    .set_selected_task_list(list);
+                match save_result {
+                    Ok(()) => self.handle_action(Action::OpenPopup {
+                        popup: self.popup_factory.create_task_list_popup(0),
+                    }),
+                    Err(error) => self.handle_action(Action::OpenPopup {
+                        popup: self.popup_factory.create_error_popup(error.to_string()),
+                    }),
+                }
+            }
+            Action::MoveTasksToList { task_uuids, list } => {
+                let move_result = self
+                    .popup_factory
+                    .task_manager
+                    .borrow_mut()
+                    .move_tasks_to_list(&task_uuids, list);
+                match move_result {
+                    Ok(()) => self.handle_action(Action::OpenPopup {
+                        popup: self.popup_factory.create_task_list_popup(0),
+                    }),
+                    Err(error) => self.handle_action(Action::OpenPopup {
+                        popup: self.popup_factory.create_error_popup(error.to_string()),
+                    }),
+                }
+            }
+            Action::RecordSession {
+                start,
+                end,
+                phase,
+                duration_seconds,
+            } => {
+                let task_manager = self.popup_factory.task_manager.borrow();
+                let active_task = task_manager.active_task();
+                let session = Session {
+                    start,
+                    end,
+                    phase,
+                    duration_seconds,
+                    task_uuid: active_task.map(|task| task.uuid),
+                    task_title: active_task.map(|task| task.title.clone()),
+                };
+                drop(task_manager);
+
+                let record_result = self
+                    .popup_factory
+                    .session_manager
+                    .borrow_mut()
+                    .add_session(session);
+                match record_result {
+                    // Left to the instance that may save them, as the banner tells
+                    Ok(()) | Err(SessionFileError::ReadOnly) => {}
+                    Err(error) => self.handle_action(Action::OpenPopup {
+                        popup: self.popup_factory.create_error_popup(error.to_string()),
+                    }),
+                }
+            }
+            Action::ExportCsv { directory } => {
+                let export_result = export_csv(
+                    Path::new(&directory),
+                    &self.popup_factory.task_manager.borrow().tasks,
+                    &self.popup_factory.session_manager.borrow().sessions,
+                );
+                match export_result {
+                    Ok(()) => self.handle_action(Action::OpenPopup {
+                        popup: self.popup_factory.create_task_list_popup(0),
+                    }),
+                    Err(error) => self.handle_action(Action::OpenPopup {
+                        popup: self.popup_factory.create_error_popup(error.to_string()),
+                    }),
+                }
+            }
+            Action::ResolveTaskConflict { keep_ours } => {
+                let resolve_result = self
+                    .popup_factory
+                    .task_manager
+                    .borrow_mut()
+                    .resolve_conflict(keep_ours);
+                match resolve_result {
+                    Ok(()) => {
+                        self.conflict_popup_open = false;
+                        self.handle_action(Action::OpenPopup {
+                            popup: self.popup_factory.create_task_list_popup(0),
+                        })
+                    }
+                    Err(error) => self.handle_action(Action::OpenPopup {
+                        popup: self.popup_factory.create_error_popup(error.to_string()),
+                    }),
+                }
+            }
             Action::OpenPopup { popup } => self.current_popup = Some(popup),
             Action::ClosePopup => self.current_popup = None,
         }
     }
+
+    /// Goes back to the task list on the changed task, or shows why the change could not be saved
+    fn show_task_change_result(
+        &mut self,
+        change_result: Result<Vec<Uuid>, SaveTaskError>,
+        task_uuid: Option<Uuid>,
+    ) {
+        match change_result {
+            Ok(completed_task_uuids) => {
+                self.handle_action(Action::TasksCompleted {
+                    task_uuids: completed_task_uuids,
+                });
+                let task_idx = self
+                    .popup_factory
+                    .task_manager
+                    .borrow()
+                    .tasks
+                    .iter()
+                    .position(|task| Some(task.uuid) == task_uuid)
+                    .unwrap_or(0);
+                self.handle_action(Action::OpenPopup {
+                    popup: self.popup_factory.create_task_list_popup(task_idx),
+                })
+            }
+            Err(error) => self.handle_action(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error.to_string()),
+            }),
+        }
+    }
+}
+
+/// Lets the user edit text in `$VISUAL` or `$EDITOR`, `vi` when neither is set, through a
+/// temporary file starting with the given name
+///
+/// The file gets a random name and is created anew, readable only by the user, so that no
+/// other user can read the text or make us write it through a link left in its place.
+fn edit_in_editor(text: &str, file_prefix: &str) -> Result<String, String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| String::from("vi"));
+    let mut editor_words = editor.split_whitespace();
+    let editor_program = editor_words.next().unwrap_or("vi");
+    let file_path = env::temp_dir().join(format!("{}-{}.md", file_prefix, Uuid::new_v4()));
+
+    let mut open_options = OpenOptions::new();
+    open_options.write(true).create_new(true);
+    #[cfg(unix)]
+    open_options.mode(0o600);
+    open_options
+        .open(&file_path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .map_err(|error| {
+            format!(
+                "Failed to write the notes to '{}': {}",
+                file_path.display(),
+                error
+            )
+        })?;
+    let edit_result = Command::new(editor_program)
+        .args(editor_words)
+        .arg(&file_path)
+        .status()
+        .map_err(|error| format!("Failed to start the editor '{}': {}", editor, error))
+        .and_then(|status| {
+            if status.success() {
+                Ok(())
+            } else {
+                Err(format!("The editor '{}' exited with {}", editor, status))
+            }
+        })
+        .and_then(|_| {
+            fs::read_to_string(&file_path).map_err(|error| {
+                format!(
+                    "Failed to read the notes back from '{}': {}",
+                    file_path.display(),
+                    error
+                )
+            })
+        });
+    let _ = fs::remove_file(&file_path);
+
+    // Editors add a final line break that the notes did not have
+    edit_result.map(|edited_text| edited_text.trim_end_matches('\n').to_string())
 }
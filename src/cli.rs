@@ -0,0 +1,478 @@
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    io::{IsTerminal, Write, stdin, stdout},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local, NaiveDate};
+use clap::Parser;
+
+#[cfg(feature = "sqlite")]
+use crate::storage::sqlite::{self, SqliteSessionStore, SqliteTaskStore};
+use crate::{
+    formats::{csv, ics, markdown, todo_txt},
+    paths::{default_config_dir, default_data_dir, move_file},
+    popups::task_list::Task,
+    sessions::{FileSessionStore, ReadOnlySessionStore, SessionManager, SessionStore},
+    storage::{
+        LoadTaskFileError, SaveTaskError, TaskManager, TaskStore,
+        file::{FileTaskStore, TaskFileFormat},
+        lock::DataLock,
+        read_only::ReadOnlyTaskStore,
+    },
+};
+
+/// Where the tasks and the session history are loaded from and saved to
+pub type Stores = (Box<dyn TaskStore>, Box<dyn SessionStore>);
+
+#[derive(Parser)]
+#[command(version, about = "A terminal user interface for focus sessions")]
+pub struct Cli {
+    /// Directory of the tasks and the session history, $XDG_DATA_HOME/focus-tui by default
+    #[arg(long, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Task file to use instead of the one in the data directory, in todo.txt format if it
+    /// ends with .txt
+    #[arg(long, value_name = "PATH")]
+    pub tasks_file: Option<PathBuf>,
+
+    /// Format of the task list, stored as tasks.json or todo.txt in the data directory
+    #[arg(long, value_enum, default_value_t = TaskFileFormat::Json)]
+    pub format: TaskFileFormat,
+
+    /// Open the tasks and the session history without saving any change to them
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Store the tasks and focus sessions in focus.db in the data directory, importing the
+    /// existing files on first use
+    #[cfg(feature = "sqlite")]
+    #[arg(long)]
+    pub sqlite: bool,
+
+    /// List the backups kept of the previous versions of the task file, then exit
+    #[arg(long)]
+    pub list_backups: bool,
+
+    /// Replace the task list with the given backup, 1 being the most recent, then exit
+    #[arg(long, value_name = "NUMBER")]
+    pub restore_backup: Option<usize>,
+
+    /// Add the tasks of a todo.txt file to the task list, then exit
+    #[arg(long, value_name = "PATH")]
+    pub import_todo_txt: Option<PathBuf>,
+
+    /// Write the task list to a todo.txt file, then exit
+    #[arg(long, value_name = "PATH")]
+    pub export_todo_txt: Option<PathBuf>,
+
+    /// Add the checklist items of a Markdown file to the task list, then exit
+    #[arg(long, value_name = "PATH")]
+    pub import_markdown: Option<PathBuf>,
+
+    /// Write the task list to a Markdown checklist, then exit
+    #[arg(long, value_name = "PATH")]
+    pub export_markdown: Option<PathBuf>,
+
+    /// Write the task list to a CSV file, then exit
+    #[arg(long, value_name = "PATH")]
+    pub export_tasks_csv: Option<PathBuf>,
+
+    /// Write the recorded focus sessions to a CSV file, then exit
+    #[arg(long, value_name = "PATH")]
+    pub export_sessions_csv: Option<PathBuf>,
+
+    /// Write the recorded work sessions to an iCalendar file, then exit
+    #[arg(long, value_name = "PATH")]
+    pub export_ics: Option<PathBuf>,
+
+    /// Only export the sessions started on or after this date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE", requires = "export_ics")]
+    pub from: Option<NaiveDate>,
+
+    /// Only export the sessions started on or before this date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE", requires = "export_ics")]
+    pub to: Option<NaiveDate>,
+
+    /// Export work sessions on the same task separated only by breaks as a single event
+    #[arg(long, requires = "export_ics")]
+    pub merge_sessions: bool,
+}
+
+impl Cli {
+    pub fn data_dir(&self) -> PathBuf {
+        self.data_dir.clone().unwrap_or_else(default_data_dir)
+    }
+
+    fn tasks_file_name(&self) -> &str {
+        match self.format {
+            TaskFileFormat::Json => "tasks.json",
+            TaskFileFormat::TodoTxt => "todo.txt",
+        }
+    }
+
+    pub fn tasks_file_path(&self) -> PathBuf {
+        self.tasks_file
+            .clone()
+            .unwrap_or_else(|| self.data_dir().join(self.tasks_file_name()))
+    }
+
+    pub fn sessions_file_path(&self) -> PathBuf {
+        self.data_dir().join("sessions.json")
+    }
+
+    #[cfg(feature = "sqlite")]
+    pub fn database_path(&self) -> PathBuf {
+        self.data_dir().join("focus.db")
+    }
+
+    pub fn lock_file_path(&self) -> PathBuf {
+        self.data_dir().join("focus-tui.lock")
+    }
+
+    pub fn settings_file_path(&self) -> PathBuf {
+        default_config_dir().join("settings.json")
+    }
+
+    pub fn create_directories(&self) -> Result<(), String> {
+        let tasks_file_path = self.tasks_file_path();
+        let directories = [
+            Some(self.data_dir()),
+            default_config_dir().into(),
+            tasks_file_path.parent().map(Path::to_path_buf),
+        ];
+        for directory in directories.into_iter().flatten() {
+            if directory.as_os_str().is_empty() {
+                continue;
+            }
+            create_dir_all(&directory).map_err(|error| {
+                format!(
+                    "Failed to create the directory {}: {}",
+                    directory.display(),
+                    error
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Files that earlier versions kept in the working directory, with where they belong now,
+    /// as long as the new location has no task file yet
+    fn local_files_to_migrate(&self) -> Vec<(PathBuf, PathBuf)> {
+        let local_tasks_file_path = Path::new(".").join(self.tasks_file_name());
+        let data_dir = self.data_dir();
+        if self.data_dir.is_some()
+            || self.tasks_file.is_some()
+            || !local_tasks_file_path.exists()
+            || self.tasks_file_path().exists()
+            || data_dir == Path::new(".")
+        {
+            return Vec::new();
+        }
+
+        let mut local_file_paths: Vec<PathBuf> = vec![
+            local_tasks_file_path.clone(),
+            self.format.archive_file_path(&local_tasks_file_path),
+            PathBuf::from("./sessions.json"),
+            PathBuf::from("./focus.db"),
+        ];
+        local_file_paths.extend(
+            FileTaskStore::new(local_tasks_file_path)
+                .backups()
+                .into_iter()
+                .map(|backup| backup.file_path),
+        );
+
+        local_file_paths
+            .into_iter()
+            .filter_map(|local_file_path| {
+                let file_name = local_file_path.file_name()?.to_owned();
+                Some((local_file_path, data_dir.join(file_name)))
+            })
+            .chain([(PathBuf::from("./settings.json"), self.settings_file_path())])
+            .filter(|(from, to)| from.exists() && !to.exists())
+            .collect()
+    }
+
+    /// Opens the storage of the tasks and the session history chosen on the command line
+    pub fn open_stores(&self, read_only: bool) -> Result<Stores, String> {
+        let (mut task_store, mut session_store): Stores = (
+            Box::new(FileTaskStore::new(self.tasks_file_path())),
+            Box::new(FileSessionStore::new(self.sessions_file_path())),
+        );
+        #[cfg(feature = "sqlite")]
+        if self.sqlite {
+            let database_path = self.database_path();
+            let connection = sqlite::open_database(
+                &database_path,
+                &self.tasks_file_path(),
+                &self.sessions_file_path(),
+            )
+            .map_err(|error| error.to_string())?;
+
+            task_store = Box::new(SqliteTaskStore::new(
+                connection.clone(),
+                database_path.clone(),
+            ));
+            session_store = Box::new(SqliteSessionStore::new(connection, database_path));
+        }
+        if read_only {
+            task_store = Box::new(ReadOnlyTaskStore::new(task_store));
+            session_store = Box::new(ReadOnlySessionStore::new(session_store));
+        }
+        Ok((task_store, session_store))
+    }
+
+    /// Loads the tasks and the session history from the storage chosen on the command line
+    pub fn open_managers(&self, read_only: bool) -> Result<(TaskManager, SessionManager), String> {
+        let (task_store, session_store) = self.open_stores(read_only)?;
+        let task_manager =
+            TaskManager::with_store(task_store).map_err(|error| error.to_string())?;
+        let session_manager =
+            SessionManager::with_store(session_store).map_err(|error| error.to_string())?;
+        Ok((task_manager, session_manager))
+    }
+
+    pub fn has_command(&self) -> bool {
+        self.list_backups
+            || self.restore_backup.is_some()
+            || self.import_todo_txt.is_some()
+            || self.export_todo_txt.is_some()
+            || self.import_markdown.is_some()
+            || self.export_markdown.is_some()
+            || self.export_tasks_csv.is_some()
+            || self.export_sessions_csv.is_some()
+            || self.export_ics.is_some()
+    }
+}
+
+/// Offers to move the files of an earlier version from the working directory to the data
+/// directory, when run from a terminal
+pub fn offer_local_files_migration(cli: &Cli) -> Result<(), String> {
+    let local_files = cli.local_files_to_migrate();
+    if local_files.is_empty() || !stdin().is_terminal() {
+        return Ok(());
+    }
+
+    println!("Found the files of an earlier version in the working directory:");
+    for (from, to) in &local_files {
+        println!("  {} -> {}", from.display(), to.display());
+    }
+    print!("Move them to their new location? [y/N] ");
+    let mut answer = String::new();
+    stdout()
+        .flush()
+        .and_then(|_| stdin().read_line(&mut answer))
+        .map_err(|error| error.to_string())?;
+
+    if !answer.trim().eq_ignore_ascii_case("y") {
+        println!("Left them in place, run with --data-dir . to keep using them");
+        return Ok(());
+    }
+    for (from, to) in &local_files {
+        move_file(from, to).map_err(|error| {
+            format!(
+                "Failed to move {} to {}: {}",
+                from.display(),
+                to.display(),
+                error
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Runs the one-off commands given on the command line, returning `None` to start the TUI instead
+pub fn run_commands(cli: &Cli) -> Option<Result<String, String>> {
+    if !cli.has_command() {
+        return None;
+    }
+    Some(run_file_commands(cli))
+}
+
+fn run_file_commands(cli: &Cli) -> Result<String, String> {
+    // Exports only read the data files, so they may run next to an instance using them
+    let _data_lock = if cli.restore_backup.is_some()
+        || cli.import_todo_txt.is_some()
+        || cli.import_markdown.is_some()
+    {
+        Some(DataLock::acquire(&cli.lock_file_path()).map_err(|error| error.to_string())?)
+    } else {
+        None
+    };
+    let (mut task_manager, session_manager) = cli.open_managers(false)?;
+    let mut messages: Vec<String> = Vec::new();
+
+    if cli.list_backups {
+        messages.push(list_backups(cli)?);
+    }
+    if let Some(backup_number) = cli.restore_backup {
+        messages.push(restore_backup(cli, &mut task_manager, backup_number)?);
+    }
+    if let Some(import_path) = &cli.import_todo_txt {
+        messages.push(import_tasks(
+            &mut task_manager,
+            import_path,
+            todo_txt::parse,
+        )?);
+    }
+    if let Some(import_path) = &cli.import_markdown {
+        messages.push(import_tasks(
+            &mut task_manager,
+            import_path,
+            markdown::parse,
+        )?);
+    }
+    if let Some(export_path) = &cli.export_todo_txt {
+        messages.push(export_tasks(
+            &task_manager,
+            export_path,
+            todo_txt::serialize,
+        )?);
+    }
+    if let Some(export_path) = &cli.export_markdown {
+        messages.push(export_tasks(
+            &task_manager,
+            export_path,
+            markdown::serialize,
+        )?);
+    }
+    if let Some(export_path) = &cli.export_tasks_csv {
+        messages.push(export_tasks(
+            &task_manager,
+            export_path,
+            csv::serialize_tasks,
+        )?);
+    }
+    if let Some(export_path) = &cli.export_sessions_csv {
+        write(
+            export_path,
+            csv::serialize_sessions(&session_manager.sessions),
+        )
+        .map_err(|error| {
+            SaveTaskError::FileWriteError(export_path.to_path_buf(), error).to_string()
+        })?;
+        messages.push(format!(
+            "Exported {} session(s) to {}",
+            session_manager.sessions.len(),
+            export_path.display()
+        ));
+    }
+    if let Some(export_path) = &cli.export_ics {
+        write(
+            export_path,
+            ics::serialize_sessions(
+                &session_manager.sessions,
+                cli.from,
+                cli.to,
+                cli.merge_sessions,
+            ),
+        )
+        .map_err(|error| {
+            SaveTaskError::FileWriteError(export_path.to_path_buf(), error).to_string()
+        })?;
+        messages.push(format!(
+            "Exported the work sessions to {}",
+            export_path.display()
+        ));
+    }
+
+    Ok(messages.join("\n"))
+}
+
+fn list_backups(cli: &Cli) -> Result<String, String> {
+    let task_store = FileTaskStore::new(cli.tasks_file_path());
+    let backups = task_store.backups();
+    if backups.is_empty() {
+        return Ok(format!("No backup of {}", cli.tasks_file_path().display()));
+    }
+
+    let backup_lines = backups.iter().map(|backup| {
+        let modified_at = backup
+            .modified_at
+            .map(|modified_at| {
+                DateTime::<Local>::from(modified_at)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let task_count = task_store
+            .load_backup(backup)
+            .map(|tasks| format!("{} task(s)", tasks.len()))
+            .unwrap_or_else(|_| "unreadable".to_string());
+        format!(
+            "{}  {}  {}  {}",
+            backup.number,
+            modified_at,
+            task_count,
+            backup.file_path.display()
+        )
+    });
+    Ok(backup_lines.collect::<Vec<String>>().join("\n"))
+}
+
+/// Restores a backup through the task manager, so the replaced list becomes a backup in turn
+fn restore_backup(
+    cli: &Cli,
+    task_manager: &mut TaskManager,
+    backup_number: usize,
+) -> Result<String, String> {
+    let task_store = FileTaskStore::new(cli.tasks_file_path());
+    let backup = task_store
+        .backups()
+        .into_iter()
+        .find(|backup| backup.number == backup_number)
+        .ok_or_else(|| format!("No backup number {} to restore", backup_number))?;
+    let tasks = task_store
+        .load_backup(&backup)
+        .map_err(|error| error.to_string())?;
+    let task_count = tasks.len();
+    task_manager
+        .replace_tasks(tasks)
+        .map_err(|error| error.to_string())?;
+
+    Ok(format!(
+        "Restored {} task(s) from {}",
+        task_count,
+        backup.file_path.display()
+    ))
+}
+
+fn import_tasks(
+    task_manager: &mut TaskManager,
+    import_path: &Path,
+    parse: fn(&str) -> Vec<Task>,
+) -> Result<String, String> {
+    let content = read_to_string(import_path).map_err(|error| {
+        LoadTaskFileError::IoError(import_path.to_path_buf(), error).to_string()
+    })?;
+    let tasks = parse(&content);
+    let task_count = tasks.len();
+    let imported_count = task_manager
+        .import_tasks(tasks)
+        .map_err(|error| error.to_string())?;
+
+    Ok(format!(
+        "Imported {} task(s) from {}, skipping {} already in the task list",
+        imported_count,
+        import_path.display(),
+        task_count - imported_count
+    ))
+}
+
+fn export_tasks(
+    task_manager: &TaskManager,
+    export_path: &Path,
+    serialize: fn(&[Task]) -> String,
+) -> Result<String, String> {
+    write(export_path, serialize(&task_manager.tasks)).map_err(|error| {
+        SaveTaskError::FileWriteError(export_path.to_path_buf(), error).to_string()
+    })?;
+
+    Ok(format!(
+        "Exported {} task(s) to {}",
+        task_manager.tasks.len(),
+        export_path.display()
+    ))
+}
@@ -0,0 +1,141 @@
+use std::{fs::create_dir_all, fs::write, path::Path};
+
+use chrono::{DateTime, Local, Utc};
+use uuid::Uuid;
+
+use crate::{
+    popups::task_list::Task, sessions::Session, storage::SaveTaskError, utils::CountdownType,
+};
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Writes `tasks.csv` and `sessions.csv` into the directory, creating it when needed
+pub fn export_csv(
+    directory: &Path,
+    tasks: &[Task],
+    sessions: &[Session],
+) -> Result<(), SaveTaskError> {
+    create_dir_all(directory)
+        .map_err(|error| SaveTaskError::FileWriteError(directory.to_path_buf(), error))?;
+
+    for (file_name, content) in [
+        ("tasks.csv", serialize_tasks(tasks)),
+        ("sessions.csv", serialize_sessions(sessions)),
+    ] {
+        let file_path = directory.join(file_name);
+        write(&file_path, content)
+            .map_err(|error| SaveTaskError::FileWriteError(file_path, error))?;
+    }
+    Ok(())
+}
+
+pub fn serialize_tasks(tasks: &[Task]) -> String {
+    let mut content = String::from(
+        "uuid,title,status,tags,priority,created_at,completed_at,list,recurrence,notes,blocked_by,estimate,due_date\n",
+    );
+    for task in tasks {
+        content.push_str(&format_row(&[
+            task.uuid.to_string(),
+            task.title.clone(),
+            task.status.to_string(),
+            task.tags.join(";"),
+            task.priority.map(String::from).unwrap_or_default(),
+            format_datetime(task.created_at),
+            format_datetime(task.completed_at),
+            task.list.clone().unwrap_or_default(),
+            task.recurrence
+                .as_ref()
+                .map(|recurrence| recurrence.to_string())
+                .unwrap_or_default(),
+            task.notes.clone(),
+            task.blocked_by
+                .iter()
+                .map(Uuid::to_string)
+                .collect::<Vec<String>>()
+                .join(";"),
+            task.estimate
+                .map(|estimate| estimate.to_string())
+                .unwrap_or_default(),
+            task.due_date
+                .map(|due_date| due_date.to_string())
+                .unwrap_or_default(),
+        ]));
+    }
+    content
+}
+
+pub fn serialize_sessions(sessions: &[Session]) -> String {
+    let mut content = String::from("start,end,phase,duration_minutes,task_uuid,task\n");
+    for session in sessions {
+        content.push_str(&format_row(&[
+            format_datetime(Some(session.start)),
+            format_datetime(Some(session.end)),
+            match session.phase {
+                CountdownType::Work => String::from("Work"),
+                CountdownType::Break => String::from("Break"),
+            },
+            (session.duration_seconds / 60).to_string(),
+            session
+                .task_uuid
+                .map(|task_uuid| task_uuid.to_string())
+                .unwrap_or_default(),
+            session.task_title.clone().unwrap_or_default(),
+        ]));
+    }
+    content
+}
+
+/// Joins the fields of a row, quoting the ones holding a separator, a quote or a line break
+///
+/// Fields that spreadsheets would run as a formula are prefixed with `'`, which they show as text.
+fn format_row(fields: &[String]) -> String {
+    let quoted_fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+                format!("'{}", field)
+            } else {
+                field.clone()
+            }
+        })
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    quoted_fields.join(",") + "\n"
+}
+
+/// Spreadsheets read local date-times more easily than RFC 3339
+fn format_datetime(datetime: Option<DateTime<Utc>>) -> String {
+    datetime
+        .map(|datetime| {
+            datetime
+                .with_timezone(&Local)
+                .format(DATETIME_FORMAT)
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_read_as_formulas_are_kept_as_text() {
+        let row = format_row(&[
+            String::from("=HYPERLINK(\"http://example.com\")"),
+            String::from("-1"),
+            String::from("@home"),
+            String::from("Write report"),
+        ]);
+        assert_eq!(
+            row,
+            "\"'=HYPERLINK(\"\"http://example.com\"\")\",'-1,'@home,Write report\n"
+        );
+    }
+}
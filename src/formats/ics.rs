@@ -0,0 +1,128 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+
+use crate::{sessions::Session, utils::CountdownType};
+
+const DATETIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Slack allowed around recorded breaks when merging the work sessions on each side
+const MERGE_TOLERANCE_SECONDS: i64 = 60;
+
+/// Consecutive work sessions shown as one calendar event
+struct FocusEvent<'a> {
+    first_session: &'a Session,
+    end: DateTime<Utc>,
+    session_count: usize,
+    focused_seconds: u64,
+}
+
+/// Writes the work sessions started between `from` and `to` (inclusive, local dates) as VEVENTs
+///
+/// With `merge_sessions`, work sessions on the same task separated only by their recorded breaks
+/// become a single event spanning all of them.
+pub fn serialize_sessions(
+    sessions: &[Session],
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    merge_sessions: bool,
+) -> String {
+    let mut events: Vec<FocusEvent> = Vec::new();
+    let mut break_seconds_since_last_work: i64 = 0;
+
+    for session in sessions {
+        let start_date = session.start.with_timezone(&Local).date_naive();
+        if from.is_some_and(|from| start_date < from) || to.is_some_and(|to| start_date > to) {
+            continue;
+        }
+        if session.phase == CountdownType::Break {
+            break_seconds_since_last_work += session.end.timestamp() - session.start.timestamp();
+            continue;
+        }
+
+        let merges_with_previous = events.last().is_some_and(|previous_event| {
+            let gap_seconds = session.start.timestamp() - previous_event.end.timestamp();
+            merge_sessions
+                && previous_event.first_session.task_uuid == session.task_uuid
+                && gap_seconds <= break_seconds_since_last_work + MERGE_TOLERANCE_SECONDS
+        });
+        break_seconds_since_last_work = 0;
+
+        match events.last_mut() {
+            Some(previous_event) if merges_with_previous => {
+                previous_event.end = session.end;
+                previous_event.session_count += 1;
+                previous_event.focused_seconds += session.duration_seconds;
+            }
+            _ => events.push(FocusEvent {
+                first_session: session,
+                end: session.end,
+                session_count: 1,
+                focused_seconds: session.duration_seconds,
+            }),
+        }
+    }
+
+    let mut lines: Vec<String> = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//focus-tui//focus sessions//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+    ];
+    let timestamp = format_datetime(Utc::now());
+    for event in events {
+        let summary = event
+            .first_session
+            .task_title
+            .clone()
+            .unwrap_or_else(|| String::from("Focus session"));
+        let focused_minutes = Duration::seconds(event.focused_seconds as i64).num_minutes();
+
+        lines.extend([
+            String::from("BEGIN:VEVENT"),
+            format!(
+                "UID:{}@focus-tui",
+                event.first_session.start.format("%Y%m%dT%H%M%S%.fZ")
+            ),
+            format!("DTSTAMP:{}", timestamp),
+            format!("DTSTART:{}", format_datetime(event.first_session.start)),
+            format!("DTEND:{}", format_datetime(event.end)),
+            format!("SUMMARY:{}", escape_text(&summary)),
+            String::from("CATEGORIES:Work"),
+            format!(
+                "DESCRIPTION:{}",
+                escape_text(&format!(
+                    "{} work session(s), {} minutes of focus",
+                    event.session_count, focused_minutes
+                ))
+            ),
+            String::from("END:VEVENT"),
+        ]);
+    }
+    lines.push(String::from("END:VCALENDAR"));
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+fn format_datetime(datetime: DateTime<Utc>) -> String {
+    datetime.format(DATETIME_FORMAT).to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits lines longer than 75 bytes, continuation lines starting with a space (RFC 5545)
+fn fold_line(line: &str) -> String {
+    let mut folded_line = String::new();
+    let mut line_length: usize = 0;
+    for c in line.chars() {
+        if line_length + c.len_utf8() > 75 {
+            folded_line.push_str("\r\n ");
+            line_length = 1;
+        }
+        folded_line.push(c);
+        line_length += c.len_utf8();
+    }
+    folded_line
+}
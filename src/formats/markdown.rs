@@ -0,0 +1,127 @@
+use uuid::Uuid;
+
+use crate::popups::task_list::{Task, TaskStatus};
+
+/// Parses the checklist items of a Markdown file
+///
+/// Headings above an item become its tags, as do `#tag` words in the item. Nested items are
+/// flattened into the list since tasks have no subtasks. Items exported by `serialize` keep
+/// their uuid in a trailing `<!-- uuid:... -->` comment, so that importing them again does not
+/// duplicate the tasks.
+pub fn parse(content: &str) -> Vec<Task> {
+    let mut headings: Vec<(usize, String)> = Vec::new();
+    let mut tasks: Vec<Task> = Vec::new();
+
+    for line in content.lines().map(str::trim) {
+        let heading_level = line.chars().take_while(|c| *c == '#').count();
+        if heading_level > 0 && line[heading_level..].starts_with(' ') {
+            headings.retain(|(level, _)| *level < heading_level);
+            headings.push((heading_level, line[heading_level..].trim().to_string()));
+            continue;
+        }
+
+        let Some((status, text)) = parse_checklist_item(line) else {
+            continue;
+        };
+        let (text, uuid) = split_uuid_comment(text);
+        let mut task = Task {
+            uuid: uuid.unwrap_or_else(Uuid::new_v4),
+            status,
+            tags: headings
+                .iter()
+                .map(|(_, heading)| heading.clone())
+                .collect(),
+            ..Default::default()
+        };
+        let mut title_words: Vec<&str> = Vec::new();
+        for word in text.split_whitespace() {
+            match word.strip_prefix('#') {
+                Some(tag) if !tag.is_empty() => task.tags.push(tag.to_string()),
+                _ => title_words.push(word),
+            }
+        }
+        task.title = title_words.join(" ");
+        tasks.push(task);
+    }
+    tasks
+}
+
+/// Writes the tasks as a checklist, untagged tasks first then under a heading per first tag
+pub fn serialize(tasks: &[Task]) -> String {
+    let mut content: String = String::new();
+    for task in tasks.iter().filter(|task| task.tags.is_empty()) {
+        content.push_str(&format_item(task, &[]));
+    }
+
+    let mut headings: Vec<&String> = Vec::new();
+    for task in tasks {
+        if let Some(heading) = task.tags.first()
+            && !headings.contains(&heading)
+        {
+            headings.push(heading);
+        }
+    }
+    for heading in headings {
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        content.push_str(&format!("## {}\n\n", heading));
+        for task in tasks
+            .iter()
+            .filter(|task| task.tags.first() == Some(heading))
+        {
+            content.push_str(&format_item(task, &task.tags[1..]));
+        }
+    }
+    content
+}
+
+/// Reads `- [ ] text`, where the box holds `x` when done and `/` or `~` when started
+fn parse_checklist_item(line: &str) -> Option<(TaskStatus, &str)> {
+    let item = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?
+        .trim_start();
+    let status = match item.get(..3)? {
+        "[ ]" => TaskStatus::initial(),
+        "[x]" | "[X]" => TaskStatus::done(),
+        "[/]" | "[~]" => TaskStatus::started(),
+        _ => return None,
+    };
+    Some((status, item[3..].trim()))
+}
+
+/// Takes the uuid out of a trailing `<!-- uuid:... -->` comment
+fn split_uuid_comment(text: &str) -> (&str, Option<Uuid>) {
+    let uuid = text
+        .strip_suffix("-->")
+        .and_then(|text| text.rsplit_once("<!--"))
+        .and_then(|(text, comment)| {
+            let uuid = Uuid::parse_str(comment.trim().strip_prefix("uuid:")?).ok()?;
+            Some((text.trim_end(), uuid))
+        });
+    match uuid {
+        Some((text, uuid)) => (text, Some(uuid)),
+        None => (text, None),
+    }
+}
+
+fn format_item(task: &Task, tags: &[String]) -> String {
+    let checkbox = if task.status.is_done() {
+        "[x]"
+    } else if task.status == TaskStatus::initial() {
+        "[ ]"
+    } else {
+        "[/]"
+    };
+    let mut item = format!("- {} {}", checkbox, task.title);
+    for tag in tags {
+        item.push_str(&format!(
+            " #{}",
+            tag.split_whitespace().collect::<Vec<&str>>().join("-")
+        ));
+    }
+    item.push_str(&format!(" <!-- uuid:{} -->", task.uuid));
+    item + "\n"
+}
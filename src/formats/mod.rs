@@ -0,0 +1,4 @@
+pub mod csv;
+pub mod ics;
+pub mod markdown;
+pub mod todo_txt;
@@ -0,0 +1,430 @@
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::{
+    popups::task_list::{Task, TaskStatus},
+    recurrence::Recurrence,
+    workflow::workflow,
+};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Keys of our own fields, which title words looking like them are escaped from
+const FIELD_KEYS: [&str; 10] = [
+    "uuid", "status", "pri", "rec", "list", "est", "due", "blocked", "notes", "created",
+];
+
+/// Parses a todo.txt file, one task per non-empty line
+pub fn parse(content: &str) -> Vec<Task> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+pub fn serialize(tasks: &[Task]) -> String {
+    tasks.iter().map(|task| format_line(task) + "\n").collect()
+}
+
+/// Parses one line such as `x 2024-01-02 2024-01-01 Write report +work @office uuid:...`
+///
+/// Projects become tags and contexts become tags starting with `@`. Our own fields that
+/// todo.txt has no syntax for are kept as `uuid:`, `status:`, `pri:`, `list:`, `rec:`,
+/// `blocked:`, `est:`, `due:` and `notes:` key/value pairs. Spaces in list names are written
+/// as `_`, the uuids of the blocking tasks are separated by `,` and the notes are escaped as
+/// told by `escape_notes`. A done task with no completion date keeps its creation date as
+/// `created:`. Title words starting with `\\` have it removed, as told by `escape_title_word`.
+/// The completion history of recurring tasks is not kept.
+pub fn parse_line(line: &str) -> Task {
+    let mut task = Task {
+        uuid: Uuid::new_v4(),
+        status: TaskStatus::initial(),
+        ..Default::default()
+    };
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let mut idx: usize = 0;
+    let next_date = |idx: &mut usize| {
+        let date = words.get(*idx).and_then(|word| parse_date(word));
+        if date.is_some() {
+            *idx += 1;
+        }
+        date
+    };
+
+    if words.first() == Some(&"x") {
+        idx += 1;
+        task.status = TaskStatus::done();
+        task.completed_at = next_date(&mut idx);
+    } else if let Some(priority) = words.first().and_then(|word| parse_priority(word)) {
+        idx += 1;
+        task.priority = Some(priority);
+    }
+    task.created_at = next_date(&mut idx);
+
+    let mut title_words: Vec<&str> = Vec::new();
+    for word in &words[idx..] {
+        match word.split_once(':') {
+            _ if word.starts_with('\\') => title_words.push(&word[1..]),
+            Some(("uuid", value)) if Uuid::parse_str(value).is_ok() => {
+                task.uuid = Uuid::parse_str(value).unwrap_or(task.uuid);
+            }
+            Some(("status", value)) if parse_status(value).is_done() == task.status.is_done() => {
+                task.status = parse_status(value);
+            }
+            Some(("pri", value)) if parse_priority_letter(value).is_some() => {
+                task.priority = parse_priority_letter(value);
+            }
+            Some(("rec", value)) if Recurrence::parse(value).is_some() => {
+                task.recurrence = Recurrence::parse(value);
+            }
+            Some(("list", value)) if !value.is_empty() => {
+                task.list = Some(value.replace('_', " "));
+            }
+            Some(("est", value)) if value.parse::<u32>().is_ok() => {
+                task.estimate = value.parse::<u32>().ok();
+            }
+            Some(("due", value)) if NaiveDate::parse_from_str(value, DATE_FORMAT).is_ok() => {
+                task.due_date = NaiveDate::parse_from_str(value, DATE_FORMAT).ok();
+            }
+            Some(("blocked", value)) if parse_uuids(value).is_some() => {
+                task.blocked_by = parse_uuids(value).unwrap_or_default();
+            }
+            Some(("notes", value)) if unescape_notes(value).is_some() => {
+                task.notes = unescape_notes(value).unwrap_or_default();
+            }
+            Some(("created", value))
+                if task.created_at.is_none() && parse_date(value).is_some() =>
+            {
+                task.created_at = parse_date(value);
+            }
+            _ if word.len() > 1 && word.starts_with('+') => task.tags.push(word[1..].to_string()),
+            _ if word.len() > 1 && word.starts_with('@') => task.tags.push(word.to_string()),
+            _ => title_words.push(word),
+        }
+    }
+    task.title = title_words.join(" ");
+    task
+}
+
+pub fn format_line(task: &Task) -> String {
+    let mut words: Vec<String> = Vec::new();
+
+    if task.status.is_done() {
+        words.push(String::from("x"));
+        // todo.txt only allows a creation date on a done task after its completion date
+        if let Some(completed_at) = task.completed_at {
+            words.push(format_date(completed_at));
+            words.extend(task.created_at.map(format_date));
+        }
+    } else {
+        words.extend(task.priority.map(|priority| format!("({})", priority)));
+        words.extend(task.created_at.map(format_date));
+    }
+
+    for (word_idx, word) in task.title.split_whitespace().enumerate() {
+        words.push(escape_title_word(word, word_idx == 0));
+    }
+    for tag in &task.tags {
+        let tag: String = tag.split_whitespace().collect::<Vec<&str>>().join("-");
+        if tag.starts_with('@') {
+            words.push(tag);
+        } else {
+            words.push(format!("+{}", tag));
+        }
+    }
+
+    // Only the statuses that `x` or its absence do not already tell
+    let implied_status = if task.status.is_done() {
+        TaskStatus::done()
+    } else {
+        TaskStatus::initial()
+    };
+    if task.status != implied_status {
+        words.push(format!(
+            "status:{}",
+            task.status.name().to_lowercase().replace(' ', "_")
+        ));
+    }
+    if task.status.is_done()
+        && let Some(priority) = task.priority
+    {
+        words.push(format!("pri:{}", priority));
+    }
+    if let Some(recurrence) = &task.recurrence {
+        words.push(format!("rec:{}", recurrence.code()));
+    }
+    if let Some(list) = &task.list {
+        words.push(format!(
+            "list:{}",
+            list.split_whitespace().collect::<Vec<&str>>().join("_")
+        ));
+    }
+    if let Some(estimate) = task.estimate {
+        words.push(format!("est:{}", estimate));
+    }
+    if let Some(due_date) = task.due_date {
+        words.push(format!("due:{}", due_date.format(DATE_FORMAT)));
+    }
+    if !task.blocked_by.is_empty() {
+        words.push(format!(
+            "blocked:{}",
+            task.blocked_by
+                .iter()
+                .map(Uuid::to_string)
+                .collect::<Vec<String>>()
+                .join(",")
+        ));
+    }
+    if !task.notes.is_empty() {
+        words.push(format!("notes:{}", escape_notes(&task.notes)));
+    }
+    if task.status.is_done()
+        && task.completed_at.is_none()
+        && let Some(created_at) = task.created_at
+    {
+        words.push(format!("created:{}", format_date(created_at)));
+    }
+    words.push(format!("uuid:{}", task.uuid));
+
+    words.join(" ")
+}
+
+/// Prefixes with `\\` a title word that would be read back as something else: a tag, one of our
+/// fields, a word starting with `\\` or, for the first word, a done mark, a date or a priority
+fn escape_title_word(word: &str, is_first_word: bool) -> String {
+    let looks_like_field = word
+        .split_once(':')
+        .is_some_and(|(key, _)| FIELD_KEYS.contains(&key));
+    let looks_like_tag = word.len() > 1 && word.starts_with(['+', '@']);
+    let looks_like_start = is_first_word
+        && (word == "x" || parse_date(word).is_some() || parse_priority(word).is_some());
+    if looks_like_field || looks_like_tag || looks_like_start || word.starts_with('\\') {
+        format!("\\{}", word)
+    } else {
+        word.to_string()
+    }
+}
+
+/// Writes the notes as a single word, `\\` standing for a backslash, `\n` for a line break,
+/// `\s` for a space, `\t` for a tab and `\u{..}` for any other whitespace
+fn escape_notes(notes: &str) -> String {
+    let mut escaped_notes = String::new();
+    for char in notes.chars() {
+        match char {
+            '\\' => escaped_notes.push_str("\\\\"),
+            '\n' => escaped_notes.push_str("\\n"),
+            ' ' => escaped_notes.push_str("\\s"),
+            '\t' => escaped_notes.push_str("\\t"),
+            char if char.is_whitespace() => {
+                escaped_notes.push_str(&format!("\\u{{{:x}}}", char as u32));
+            }
+            char => escaped_notes.push(char),
+        }
+    }
+    escaped_notes
+}
+
+/// Reads notes written by `escape_notes`, failing on an unknown escape
+fn unescape_notes(value: &str) -> Option<String> {
+    let mut notes = String::new();
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            notes.push(char);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => notes.push('\\'),
+            'n' => notes.push('\n'),
+            's' => notes.push(' '),
+            't' => notes.push('\t'),
+            'u' => {
+                if chars.next() != Some('{') {
+                    return None;
+                }
+                let code: String = chars.by_ref().take_while(|char| *char != '}').collect();
+                notes.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+            }
+            _ => return None,
+        }
+    }
+    Some(notes)
+}
+
+/// Reads uuids separated by `,`, failing if any is not one
+fn parse_uuids(value: &str) -> Option<Vec<Uuid>> {
+    value
+        .split(',')
+        .map(|uuid| Uuid::parse_str(uuid).ok())
+        .collect()
+}
+
+/// Reads a status written as `in_review`, matching the workflow regardless of case
+fn parse_status(value: &str) -> TaskStatus {
+    let name = value.replace('_', " ");
+    workflow()
+        .statuses
+        .iter()
+        .find(|status| status.name.eq_ignore_ascii_case(&name))
+        .map(|status| TaskStatus::new(&status.name))
+        .unwrap_or_else(|| TaskStatus::new(&name))
+}
+
+/// Reads a priority written as `(A)`
+fn parse_priority(word: &str) -> Option<char> {
+    parse_priority_letter(word.strip_prefix('(')?.strip_suffix(')')?)
+}
+
+fn parse_priority_letter(letters: &str) -> Option<char> {
+    let mut chars = letters.chars();
+    match (chars.next(), chars.next()) {
+        (Some(priority), None) if priority.is_ascii_uppercase() => Some(priority),
+        _ => None,
+    }
+}
+
+/// Reads a date as the start of that day in the local time zone
+fn parse_date(word: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(word, DATE_FORMAT).ok()?;
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|local_datetime| local_datetime.with_timezone(&Utc))
+}
+
+fn format_date(datetime: DateTime<Utc>) -> String {
+    datetime
+        .with_timezone(&Local)
+        .format(DATE_FORMAT)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use super::*;
+
+    #[test]
+    fn recurring_tasks_keep_their_rule() {
+        let task = Task {
+            title: String::from("Water the plants"),
+            status: TaskStatus::initial(),
+            recurrence: Some(Recurrence::Weekly(vec![Weekday::Wed])),
+            ..Default::default()
+        };
+
+        let parsed_task = parse_line(&format_line(&task));
+        assert_eq!(parsed_task.title, "Water the plants");
+        assert!(parsed_task.recurrence == task.recurrence);
+    }
+
+    #[test]
+    fn fields_are_kept() {
+        let blocking_uuid = Uuid::new_v4();
+        let task = Task {
+            title: String::from("Write report"),
+            status: TaskStatus::new("Ongoing"),
+            created_at: parse_date("2024-01-01"),
+            tags: vec![String::from("work"), String::from("@office")],
+            priority: Some('B'),
+            list: Some(String::from("Side project")),
+            estimate: Some(3),
+            due_date: NaiveDate::from_ymd_opt(2024, 2, 1),
+            blocked_by: vec![blocking_uuid],
+            ..Default::default()
+        };
+
+        let parsed_task = parse_line(&format_line(&task));
+        assert_eq!(parsed_task.uuid, task.uuid);
+        assert_eq!(parsed_task.title, task.title);
+        assert!(parsed_task.status == task.status);
+        assert_eq!(parsed_task.created_at, task.created_at);
+        assert_eq!(parsed_task.tags, task.tags);
+        assert_eq!(parsed_task.priority, task.priority);
+        assert_eq!(parsed_task.list, task.list);
+        assert_eq!(parsed_task.estimate, task.estimate);
+        assert_eq!(parsed_task.due_date, task.due_date);
+        assert_eq!(parsed_task.blocked_by, task.blocked_by);
+    }
+
+    #[test]
+    fn titles_starting_like_a_line_are_kept() {
+        for title in [
+            "x marks the spot",
+            "2024-01-01 review",
+            "(A) team",
+            "\\server down",
+        ] {
+            let task = Task {
+                title: String::from(title),
+                status: TaskStatus::initial(),
+                ..Default::default()
+            };
+
+            let parsed_task = parse_line(&format_line(&task));
+            assert_eq!(parsed_task.title, title);
+            assert!(parsed_task.status == TaskStatus::initial());
+            assert_eq!(parsed_task.priority, None);
+            assert_eq!(parsed_task.created_at, None);
+        }
+    }
+
+    #[test]
+    fn title_words_looking_like_fields_are_kept() {
+        let task = Task {
+            title: String::from("Email +bob @home about uuid:x due:2024-01-01 notes:a \\s"),
+            status: TaskStatus::initial(),
+            ..Default::default()
+        };
+
+        let parsed_task = parse_line(&format_line(&task));
+        assert_eq!(parsed_task.title, task.title);
+        assert!(parsed_task.tags.is_empty());
+        assert_eq!(parsed_task.due_date, None);
+        assert_eq!(parsed_task.notes, "");
+        assert_eq!(parsed_task.uuid, task.uuid);
+    }
+
+    #[test]
+    fn done_tasks_keep_their_dates() {
+        let created_at = parse_date("2024-01-01");
+        let completed_at = parse_date("2024-01-03");
+        for (created_at, completed_at) in [
+            (created_at, None),
+            (None, completed_at),
+            (created_at, completed_at),
+        ] {
+            let task = Task {
+                title: String::from("2024-01-02 meeting"),
+                status: TaskStatus::done(),
+                created_at,
+                completed_at,
+                ..Default::default()
+            };
+
+            let parsed_task = parse_line(&format_line(&task));
+            assert_eq!(parsed_task.title, task.title);
+            assert!(parsed_task.status.is_done());
+            assert_eq!(parsed_task.created_at, created_at);
+            assert_eq!(parsed_task.completed_at, completed_at);
+        }
+    }
+
+    #[test]
+    fn notes_are_kept_on_a_single_line() {
+        let task = Task {
+            title: String::from("Write report"),
+            status: TaskStatus::initial(),
+            notes: String::from("Outline:\n\t- intro \\ summary\u{a0}end\n\nsee notes:here"),
+            ..Default::default()
+        };
+
+        let line = format_line(&task);
+        assert!(!line.contains('\n'));
+        let parsed_task = parse_line(&line);
+        assert_eq!(parsed_task.title, "Write report");
+        assert_eq!(parsed_task.notes, task.notes);
+    }
+}
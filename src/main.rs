@@ -1,16 +1,177 @@
 mod app;
+mod cli;
+mod formats;
+mod paths;
 mod popup_factory;
 mod popups;
+mod quick_add;
+mod recurrence;
 mod screens;
+mod sessions;
+mod settings;
 mod storage;
 mod theme;
 mod utils;
+mod workflow;
 use app::App;
-use std::io;
+use clap::Parser;
+use cli::Cli;
+use ratatui::DefaultTerminal;
+use screens::recovery::{RecoveryChoice, RecoveryScreen};
+use sessions::SessionManager;
+use settings::SettingsManager;
+use std::{io, process::ExitCode};
+use storage::{
+    TaskManager,
+    file::FileTaskStore,
+    lock::{DataLock, LockDataError},
+};
+use theme::Theme;
+
+fn main() -> io::Result<ExitCode> {
+    let cli = Cli::parse();
+    let prepare_result = cli.create_directories().and_then(|_| {
+        if cli.has_command() {
+            Ok(())
+        } else {
+            cli::offer_local_files_migration(&cli)
+        }
+    });
+    if let Err(error) = prepare_result {
+        eprintln!("{}", error);
+        return Ok(ExitCode::FAILURE);
+    }
+    let (settings_manager, settings_error) = SettingsManager::new(cli.settings_file_path());
+    // Also used by the commands, to read and write statuses in other formats
+    let workflow_error = workflow::set_workflow(settings_manager.settings.workflow.clone()).err();
+    // Printed before the TUI starts, and shown in it as well since it hides them
+    let mut startup_errors: Vec<String> = settings_error
+        .map(|error| error.to_string())
+        .into_iter()
+        .chain(workflow_error.map(|error| error.to_string()))
+        .collect();
+    for startup_error in &startup_errors {
+        eprintln!("{}", startup_error);
+    }
+
+    match cli::run_commands(&cli) {
+        Some(Ok(message)) => {
+            println!("{}", message);
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Err(error)) => {
+            eprintln!("{}", error);
+            return Ok(ExitCode::FAILURE);
+        }
+        None => {}
+    }
+
+    // A second instance follows the changes of the first one without saving any of its own
+    let data_lock = if cli.read_only {
+        None
+    } else {
+        match DataLock::acquire(&cli.lock_file_path()) {
+            Ok(data_lock) => Some(data_lock),
+            Err(LockDataError::HeldElsewhere(_)) => None,
+            Err(error) => {
+                eprintln!("{}", error);
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    };
+    let banner = match (&data_lock, cli.read_only) {
+        (Some(_), _) => None,
+        (None, true) => Some("Read-only"),
+        (None, false) => Some("Read-only: another instance is using the tasks"),
+    };
 
-fn main() -> io::Result<()> {
     let mut terminal = ratatui::init();
-    let app_result = App::new().run(&mut terminal);
+    let app_result = match load_managers(&cli, &mut terminal, banner) {
+        Ok(Some((task_manager, session_manager, banner))) => {
+            startup_errors.extend(task_manager.dependency_cycle_warning());
+            let mut app = App::new(task_manager, session_manager, settings_manager, banner);
+            if !startup_errors.is_empty() {
+                app.show_error(startup_errors.join("\n"));
+            }
+            app.run(&mut terminal).map(|_| ExitCode::SUCCESS)
+        }
+        Ok(None) => Ok(ExitCode::SUCCESS),
+        Err(error) => {
+            ratatui::restore();
+            eprintln!("{}", error);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
     ratatui::restore();
     app_result
 }
+
+/// Managers of the loaded data, with the banner to show about them
+type LoadedManagers = (TaskManager, SessionManager, Option<&'static str>);
+
+/// Loads the tasks and the session history, letting the user recover from tasks that cannot be
+/// loaded, and returns `None` when they choose to quit instead
+fn load_managers(
+    cli: &Cli,
+    terminal: &mut DefaultTerminal,
+    banner: Option<&'static str>,
+) -> Result<Option<LoadedManagers>, String> {
+    let read_only = banner.is_some();
+    loop {
+        let (task_store, session_store) = cli.open_stores(read_only)?;
+        let session_manager =
+            SessionManager::with_store(session_store).map_err(|error| error.to_string())?;
+        let load_error = match TaskManager::with_store(task_store) {
+            Ok(task_manager) => return Ok(Some((task_manager, session_manager, banner))),
+            Err(load_error) => load_error,
+        };
+
+        let file_task_store = FileTaskStore::new(cli.tasks_file_path());
+        let backups = if !read_only && load_error.file_path() == cli.tasks_file_path() {
+            file_task_store
+                .backups()
+                .into_iter()
+                .filter_map(|backup| {
+                    let task_count = file_task_store.load_backup(&backup).ok()?.len();
+                    Some((backup, task_count))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let recovery_choice = RecoveryScreen::new(&load_error, backups, !read_only)
+            .run(terminal, &Theme::catppuccin_mocha())
+            .map_err(|error| error.to_string())?;
+
+        match recovery_choice {
+            // Saved through a task manager, so that the file being replaced becomes a backup
+            RecoveryChoice::RestoreBackup(backup) => {
+                let tasks = file_task_store
+                    .load_backup(&backup)
+                    .map_err(|error| error.to_string())?;
+                let (task_store, _) = cli.open_stores(false)?;
+                TaskManager::with_store_or_empty(task_store)
+                    .replace_tasks(tasks)
+                    .map_err(|error| error.to_string())?;
+            }
+            RecoveryChoice::MoveAside => {
+                paths::move_aside(load_error.file_path()).map_err(|error| {
+                    format!(
+                        "Failed to move {} aside: {}",
+                        load_error.file_path().display(),
+                        error
+                    )
+                })?;
+            }
+            RecoveryChoice::ReadOnly => {
+                let (task_store, _) = cli.open_stores(true)?;
+                return Ok(Some((
+                    TaskManager::with_store_or_empty(task_store),
+                    session_manager,
+                    Some("Read-only: the tasks could not be loaded"),
+                )));
+            }
+            RecoveryChoice::Quit => return Ok(None),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::{
+    env,
+    fs::{copy, remove_file, rename},
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+
+const APP_DIR_NAME: &str = "focus-tui";
+
+/// `$XDG_DATA_HOME/focus-tui`, or `~/.local/share/focus-tui` when the variable is unset
+pub fn default_data_dir() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// `$XDG_CONFIG_HOME/focus-tui`, or `~/.config/focus-tui` when the variable is unset
+pub fn default_config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// Relative values are ignored, as the XDG specification requires, and without a home directory
+/// the working directory is used as before
+fn xdg_dir(variable_name: &str, home_relative_path: &str) -> PathBuf {
+    env::var_os(variable_name)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(home_relative_path)))
+        .map(|path| path.join(APP_DIR_NAME))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Renames the file, or copies then removes it when the destination is on another file system
+pub fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy(from, to)?;
+    remove_file(from)
+}
+
+/// Renames a file that cannot be read to a dated name next to it, so a new one can be started
+pub fn move_aside(file_path: &Path) -> std::io::Result<PathBuf> {
+    let mut file_name = file_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(Local::now().format(".broken-%Y%m%d-%H%M%S").to_string());
+    let moved_file_path = file_path.with_file_name(file_name);
+
+    rename(file_path, &moved_file_path)?;
+    Ok(moved_file_path)
+}
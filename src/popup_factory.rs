@@ -1,33 +1,208 @@
 use std::{cell::RefCell, rc::Rc};
 
+use uuid::Uuid;
+
 use crate::{
-    app::Popup,
-    popups::{add_task::AddTaskPopup, error::ErrorPopup, task_list::TaskListPopup},
+    app::{Action, Popup},
+    popups::{
+        add_task::AddTaskPopup,
+        archive::ArchivePopup,
+        conflict::ConflictPopup,
+        error::ErrorPopup,
+        help::HelpPopup,
+        input::InputPopup,
+        set_status::SetStatusPopup,
+        task_detail::TaskDetailPopup,
+        task_list::{TaskListPopup, parse_list_name},
+    },
+    sessions::SessionManager,
+    settings::SettingsManager,
     storage::TaskManager,
 };
 
 #[derive(Clone)]
 pub struct PopupFactory {
     pub task_manager: Rc<RefCell<TaskManager>>,
+    pub settings_manager: Rc<RefCell<SettingsManager>>,
+    pub session_manager: Rc<RefCell<SessionManager>>,
 }
 
 impl PopupFactory {
-    pub fn new(task_manager: Rc<RefCell<TaskManager>>) -> Self {
-        PopupFactory { task_manager }
+    pub fn new(
+        task_manager: Rc<RefCell<TaskManager>>,
+        settings_manager: Rc<RefCell<SettingsManager>>,
+        session_manager: Rc<RefCell<SessionManager>>,
+    ) -> Self {
+        PopupFactory {
+            task_manager,
+            settings_manager,
+            session_manager,
+        }
     }
 
     pub fn create_task_list_popup(&self, selected_task_idx: usize) -> Box<dyn Popup> {
         Box::new(TaskListPopup::new(
             Rc::new(self.clone()),
             self.task_manager.clone(),
+            self.settings_manager.clone(),
             selected_task_idx,
         ))
     }
 
+    pub fn create_task_detail_popup(
+        &self,
+        task_uuid: Uuid,
+        task_opened_on_idx: usize,
+    ) -> Box<dyn Popup> {
+        Box::new(TaskDetailPopup::new(
+            Rc::new(self.clone()),
+            self.task_manager.clone(),
+            task_uuid,
+            task_opened_on_idx,
+        ))
+    }
+
+    pub fn create_archive_popup(&self) -> Box<dyn Popup> {
+        Box::new(ArchivePopup::new(
+            Rc::new(self.clone()),
+            self.task_manager.clone(),
+        ))
+    }
+
     pub fn create_add_task_popup(&self, task_opened_on_idx: usize) -> Box<dyn Popup> {
         Box::new(AddTaskPopup::new(Rc::new(self.clone()), task_opened_on_idx))
     }
 
+    pub fn create_edit_task_popup(&self, task_idx: usize) -> Box<dyn Popup> {
+        Box::new(AddTaskPopup::edit(
+            Rc::new(self.clone()),
+            task_idx,
+            &self.task_manager.borrow().tasks[task_idx],
+        ))
+    }
+
+    pub fn create_set_status_popup(
+        &self,
+        task_uuids: Vec<Uuid>,
+        task_opened_on_idx: usize,
+    ) -> Box<dyn Popup> {
+        Box::new(SetStatusPopup::new(
+            Rc::new(self.clone()),
+            task_uuids,
+            task_opened_on_idx,
+        ))
+    }
+
+    pub fn create_tag_tasks_popup(
+        &self,
+        task_uuids: Vec<Uuid>,
+        task_opened_on_idx: usize,
+    ) -> Box<dyn Popup> {
+        let popup_factory = Rc::new(self.clone());
+        Box::new(InputPopup::new(
+            format!("Tag {} task(s)", task_uuids.len()),
+            String::from("Tag"),
+            String::from(""),
+            Box::new(move |tag| Action::TagTasks {
+                task_uuids: task_uuids.clone(),
+                tag,
+            }),
+            Box::new(move || Action::OpenPopup {
+                popup: popup_factory.create_task_list_popup(task_opened_on_idx),
+            }),
+        ))
+    }
+
+    /// Asks for the repeat rule of the tasks, starting from the current rule of the first one
+    pub fn create_recurrence_popup(
+        &self,
+        task_uuids: Vec<Uuid>,
+        task_opened_on_idx: usize,
+    ) -> Box<dyn Popup> {
+        let current_recurrence = self
+            .task_manager
+            .borrow()
+            .tasks
+            .iter()
+            .find(|task| task_uuids.first() == Some(&task.uuid))
+            .and_then(|task| task.recurrence.as_ref())
+            .map(|recurrence| recurrence.to_string())
+            .unwrap_or_default();
+        let popup_factory = Rc::new(self.clone());
+        Box::new(InputPopup::new(
+            format!(
+                "Repeat {} task(s): daily, weekdays, weekly mon thu, every 3 days, or empty for never",
+                task_uuids.len()
+            ),
+            String::from("Repeat"),
+            current_recurrence,
+            Box::new(move |recurrence| Action::SetTasksRecurrence {
+                task_uuids: task_uuids.clone(),
+                recurrence,
+            }),
+            Box::new(move || Action::OpenPopup {
+                popup: popup_factory.create_task_list_popup(task_opened_on_idx),
+            }),
+        ))
+    }
+
+    /// Asks for the name of a list to show, creating it when no task is in it yet
+    pub fn create_select_list_popup(&self, task_opened_on_idx: usize) -> Box<dyn Popup> {
+        let popup_factory = Rc::new(self.clone());
+        Box::new(InputPopup::new(
+            String::from("Switch to a list, or name a new one"),
+            String::from("List"),
+            String::from(""),
+            Box::new(|list| Action::SelectTaskList {
+                list: parse_list_name(&list),
+            }),
+            Box::new(move || Action::OpenPopup {
+                popup: popup_factory.create_task_list_popup(task_opened_on_idx),
+            }),
+        ))
+    }
+
+    pub fn create_move_to_list_popup(
+        &self,
+        task_uuids: Vec<Uuid>,
+        task_opened_on_idx: usize,
+    ) -> Box<dyn Popup> {
+        let popup_factory = Rc::new(self.clone());
+        Box::new(InputPopup::new(
+            format!("Move {} task(s) to another list", task_uuids.len()),
+            String::from("List"),
+            String::from(""),
+            Box::new(move |list| Action::MoveTasksToList {
+                task_uuids: task_uuids.clone(),
+                list: parse_list_name(&list),
+            }),
+            Box::new(move || Action::OpenPopup {
+                popup: popup_factory.create_task_list_popup(task_opened_on_idx),
+            }),
+        ))
+    }
+
+    pub fn create_export_csv_popup(&self, task_opened_on_idx: usize) -> Box<dyn Popup> {
+        let popup_factory = Rc::new(self.clone());
+        Box::new(InputPopup::new(
+            String::from("Export tasks.csv and sessions.csv"),
+            String::from("Directory"),
+            String::from("."),
+            Box::new(|directory| Action::ExportCsv { directory }),
+            Box::new(move || Action::OpenPopup {
+                popup: popup_factory.create_task_list_popup(task_opened_on_idx),
+            }),
+        ))
+    }
+
+    pub fn create_task_list_help_popup(&self, task_opened_on_idx: usize) -> Box<dyn Popup> {
+        Box::new(HelpPopup::new(Rc::new(self.clone()), task_opened_on_idx))
+    }
+
+    pub fn create_conflict_popup(&self) -> Box<dyn Popup> {
+        Box::new(ConflictPopup::new())
+    }
+
     pub fn create_error_popup(&self, error_content: String) -> Box<dyn Popup> {
         Box::new(ErrorPopup::new(error_content))
     }
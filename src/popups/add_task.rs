@@ -1,12 +1,13 @@
 use std::rc::Rc;
 
+use chrono::{Local, Utc};
 use ratatui::{
     Frame,
     crossterm::event::{Event, KeyCode, KeyEvent},
     layout::{Alignment, Constraint, Layout, Rect},
-    style::Style,
+    style::{Modifier, Style},
     text::Text,
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
 use uuid::Uuid;
 
@@ -14,6 +15,7 @@ use crate::{
     app::{Action, Popup},
     popup_factory::PopupFactory,
     popups::task_list::{Task, TaskStatus},
+    quick_add::QuickAdd,
     theme::Theme,
     utils::popup_area,
 };
@@ -29,6 +31,8 @@ pub struct AddTaskPopup {
     current_title: String,
     current_status: TaskStatus,
     selected_field: SelectedField,
+    /// Set when the popup edits an existing task instead of adding a new one
+    edited_task_uuid: Option<Uuid>,
 }
 
 impl AddTaskPopup {
@@ -37,11 +41,58 @@ impl AddTaskPopup {
             popup_factory,
             task_opened_on_idx,
             current_title: String::from(""),
-            current_status: TaskStatus::Todo,
+            current_status: TaskStatus::initial(),
             selected_field: SelectedField::Title,
+            edited_task_uuid: None,
         }
     }
 
+    pub fn edit(popup_factory: Rc<PopupFactory>, task_opened_on_idx: usize, task: &Task) -> Self {
+        AddTaskPopup {
+            current_title: task.title.clone(),
+            current_status: task.status.clone(),
+            edited_task_uuid: Some(task.uuid),
+            ..AddTaskPopup::new(popup_factory, task_opened_on_idx)
+        }
+    }
+
+    /// New tasks take their tags, priority, estimate and due date from the title as typed
+    fn quick_add(&self) -> QuickAdd {
+        QuickAdd::parse(&self.current_title, Local::now().date_naive())
+    }
+
+    /// Fields read from the title so far, or how to type them
+    fn quick_add_preview(&self) -> String {
+        let quick_add = self.quick_add();
+        if !quick_add.has_fields() {
+            return String::from(
+                "Type #tag, !p1 for the priority, ~3 focus sessions or due:fri in the title",
+            );
+        }
+
+        let mut fields: Vec<String> = Vec::new();
+        if !quick_add.tags.is_empty() {
+            fields.push(
+                quick_add
+                    .tags
+                    .iter()
+                    .map(|tag| format!("#{}", tag))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            );
+        }
+        if let Some(priority) = quick_add.priority {
+            fields.push(format!("priority {}", priority));
+        }
+        if let Some(estimate) = quick_add.estimate {
+            fields.push(format!("{} focus session(s)", estimate));
+        }
+        if let Some(due_date) = quick_add.due_date {
+            fields.push(format!("due {}", due_date.format("%a %Y-%m-%d")));
+        }
+        format!("\"{}\": {}", quick_add.title, fields.join(", "))
+    }
+
     fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
         match key_event.code {
             // KeyCode::Esc => Some(Action::ClosePopup),
@@ -50,13 +101,27 @@ impl AddTaskPopup {
                     .popup_factory
                     .create_task_list_popup(self.task_opened_on_idx),
             }),
-            KeyCode::Enter => Some(Action::AddTask {
-                task: Task {
-                    uuid: Uuid::new_v4(),
-                    title: self.current_title.clone(),
-                    status: self.current_status.clone(),
-                },
+            KeyCode::Enter if self.edited_task_uuid.is_some() => Some(Action::EditTask {
+                task_uuid: self.edited_task_uuid?,
+                title: self.current_title.clone(),
+                status: self.current_status.clone(),
             }),
+            KeyCode::Enter => {
+                let quick_add = self.quick_add();
+                Some(Action::AddTask {
+                    task: Box::new(Task {
+                        uuid: Uuid::new_v4(),
+                        title: quick_add.title,
+                        status: self.current_status.clone(),
+                        created_at: Some(Utc::now()),
+                        tags: quick_add.tags,
+                        priority: quick_add.priority,
+                        estimate: quick_add.estimate,
+                        due_date: quick_add.due_date,
+                        ..Default::default()
+                    }),
+                })
+            }
             KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
                 match self.selected_field {
                     SelectedField::Title => self.selected_field = SelectedField::Status,
@@ -102,11 +167,17 @@ impl Popup for AddTaskPopup {
                 Constraint::Length(1),
                 Constraint::Fill(1),
                 Constraint::Length(3),
+                Constraint::Length(2),
                 Constraint::Fill(1),
             ])
             .split(inner_area);
 
-        let title_text: Text = Text::styled("Add task popup", theme.text_style()).centered();
+        let popup_title = if self.edited_task_uuid.is_some() {
+            "Edit task popup"
+        } else {
+            "Add task popup"
+        };
+        let title_text: Text = Text::styled(popup_title, theme.text_style()).centered();
         let title_paragraphe: Paragraph = Paragraph::new(title_text).centered();
 
         let task_chunks = Layout::horizontal([
@@ -144,6 +215,15 @@ impl Popup for AddTaskPopup {
         frame.render_widget(title_paragraphe, inner_layout[0]);
         frame.render_widget(task_title_paragraph, task_chunks[1]);
         frame.render_widget(status_paragraph, task_chunks[2]);
+        if self.edited_task_uuid.is_none() {
+            let preview_paragraph: Paragraph = Paragraph::new(Text::styled(
+                self.quick_add_preview(),
+                theme.text_style().add_modifier(Modifier::DIM),
+            ))
+            .wrap(Wrap { trim: true })
+            .centered();
+            frame.render_widget(preview_paragraph, inner_layout[3]);
+        }
     }
 
     fn handle_event(&mut self, event: &Event) -> Option<Action> {
@@ -153,4 +233,8 @@ impl Popup for AddTaskPopup {
             _ => None,
         }
     }
+
+    fn is_typing(&self) -> bool {
+        matches!(self.selected_field, SelectedField::Title)
+    }
 }
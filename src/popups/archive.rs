@@ -0,0 +1,145 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    text::Text,
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::{
+    app::{Action, Popup},
+    popup_factory::PopupFactory,
+    storage::TaskManager,
+    theme::Theme,
+    utils::popup_area,
+};
+
+pub struct ArchivePopup {
+    popup_factory: Rc<PopupFactory>,
+    task_manager: Rc<RefCell<TaskManager>>,
+    list_state: ListState,
+}
+
+impl ArchivePopup {
+    pub fn new(popup_factory: Rc<PopupFactory>, task_manager: Rc<RefCell<TaskManager>>) -> Self {
+        let mut list_state = ListState::default();
+        if !task_manager.borrow().archived_tasks.is_empty() {
+            list_state.select(Some(0));
+        }
+
+        ArchivePopup {
+            popup_factory,
+            task_manager,
+            list_state,
+        }
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
+        match key_event.code {
+            KeyCode::Char('A') | KeyCode::Esc => {
+                return Some(Action::OpenPopup {
+                    popup: self.popup_factory.create_task_list_popup(0),
+                });
+            }
+            KeyCode::Char('r') | KeyCode::Enter => {
+                if let Some(selected_index) = self.list_state.selected() {
+                    let selected_task_uuid =
+                        self.task_manager.borrow().archived_tasks[selected_index].uuid;
+
+                    let restore_result = self
+                        .task_manager
+                        .borrow_mut()
+                        .restore_archived_task(selected_task_uuid);
+
+                    match restore_result {
+                        Ok(_) => {
+                            let new_len = self.task_manager.borrow().archived_tasks.len();
+                            if new_len == 0 {
+                                self.list_state.select(None);
+                            } else if selected_index >= new_len {
+                                self.list_state.select(Some(new_len - 1));
+                            }
+                        }
+                        Err(error) => {
+                            return Some(Action::OpenPopup {
+                                popup: self.popup_factory.create_error_popup(error.to_string()),
+                            });
+                        }
+                    }
+                }
+            }
+            KeyCode::Up => {
+                if let Some(selected_index) = self.list_state.selected() {
+                    self.list_state
+                        .select(Some(selected_index.saturating_sub(1)));
+                }
+            }
+            KeyCode::Down => {
+                if let Some(selected_index) = self.list_state.selected() {
+                    let new_selected_index = usize::min(
+                        selected_index.saturating_add(1),
+                        self.task_manager.borrow().archived_tasks.len() - 1,
+                    );
+                    self.list_state.select(Some(new_selected_index));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl Popup for ArchivePopup {
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect, theme: &Theme) {
+        let block = Block::bordered()
+            .border_style(theme.border_style)
+            .style(theme.background_style);
+
+        let popup_area: Rect = popup_area(area, 60, 60);
+        frame.render_widget(Clear, popup_area);
+        let inner_area: Rect = block.inner(popup_area);
+        let inner_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+            ])
+            .split(inner_area);
+
+        let title_text: Text = Text::styled("Archive popup", theme.text_style()).centered();
+        let title_paragraphe: Paragraph = Paragraph::new(title_text).centered();
+
+        let archive_list: List = List::new(
+            self.task_manager
+                .borrow()
+                .archived_tasks
+                .iter()
+                .map(|task| task.get_list_item(false).style(theme.text_style()))
+                .collect::<Vec<ListItem>>(),
+        )
+        .highlight_style(Style::default().fg(theme.text_color).bold());
+
+        let controls_text: Text = Text::styled(
+            "Controls: Enter/R to restore, Esc/A to go back to the task list",
+            theme.text_style(),
+        )
+        .centered();
+        let controls_paragraph: Paragraph = Paragraph::new(controls_text).centered();
+
+        frame.render_widget(block, popup_area);
+        frame.render_widget(title_paragraphe, inner_layout[0]);
+        frame.render_stateful_widget(archive_list, inner_layout[1], &mut self.list_state);
+        frame.render_widget(controls_paragraph, inner_layout[2]);
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event),
+
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    text::Text,
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+
+use crate::{
+    app::{Action, Popup},
+    theme::Theme,
+    utils::popup_area,
+};
+
+/// Asks which version to keep when a change conflicts with changes made to the stored tasks
+pub struct ConflictPopup {}
+
+impl ConflictPopup {
+    pub fn new() -> Self {
+        ConflictPopup {}
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
+        match key_event.code {
+            KeyCode::Char('k') => Some(Action::ResolveTaskConflict { keep_ours: true }),
+            KeyCode::Char('r') => Some(Action::ResolveTaskConflict { keep_ours: false }),
+            _ => None,
+        }
+    }
+}
+
+impl Popup for ConflictPopup {
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect, theme: &Theme) {
+        let block = Block::bordered()
+            .border_style(theme.border_style)
+            .style(theme.background_style);
+
+        let popup_area: Rect = popup_area(area, 60, 40);
+        frame.render_widget(Clear, popup_area);
+        let inner_area: Rect = block.inner(popup_area);
+        let inner_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(2),
+            ])
+            .split(inner_area);
+
+        let title_text: Text = Text::styled("Conflict popup", theme.text_style()).centered();
+        let title_paragraph: Paragraph = Paragraph::new(title_text).centered();
+
+        let content_paragraph: Paragraph = Paragraph::new(
+            Text::styled(
+                "The tasks were also changed elsewhere, by hand or by another instance, \
+                and both versions changed the same tasks.",
+                theme.error_text_style(),
+            )
+            .centered(),
+        )
+        .wrap(Wrap { trim: true });
+
+        let controls_paragraph: Paragraph = Paragraph::new(
+            Text::styled(
+                "Controls: K to keep your version and overwrite the other one, \
+                R to reload the other version and drop your change",
+                theme.text_style(),
+            )
+            .centered(),
+        )
+        .wrap(Wrap { trim: true });
+
+        frame.render_widget(block, popup_area);
+        frame.render_widget(title_paragraph, inner_layout[0]);
+        frame.render_widget(content_paragraph, inner_layout[1]);
+        frame.render_widget(controls_paragraph, inner_layout[2]);
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event),
+
+            _ => None,
+        }
+    }
+}
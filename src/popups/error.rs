@@ -21,7 +21,7 @@ impl ErrorPopup {
 
     fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
         match key_event.code {
-            KeyCode::Esc => return Some(Action::ClosePopup),
+            KeyCode::Esc => Some(Action::ClosePopup),
             _ => None,
         }
     }
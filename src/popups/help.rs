@@ -0,0 +1,156 @@
+use std::rc::Rc;
+
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{
+    app::{Action, Popup},
+    popup_factory::PopupFactory,
+    theme::Theme,
+    utils::popup_area,
+};
+
+/// Every key of the task list with what it does, as `?` shows them
+const TASK_LIST_KEYS: [(&str, &str); 26] = [
+    ("Up/Down", "Select a task"),
+    ("Enter", "Open the details and notes of the task"),
+    ("A", "Add a task"),
+    ("E", "Edit the task"),
+    ("D", "Delete the task, or the marked tasks"),
+    ("Shift+Up/Down", "Move the task up or down"),
+    ("U / Ctrl+R", "Undo / redo the last change"),
+    ("Left/Right", "Change the status"),
+    ("C", "Complete the task, or take it back"),
+    ("Shift+S", "Set the status of the task, or the marked tasks"),
+    ("Space", "Mark the task"),
+    ("V", "Start or end a range of marked tasks"),
+    ("#", "Tag the task, or the marked tasks"),
+    ("B", "Make the task blocked by the marked tasks"),
+    ("Shift+R", "Repeat the task daily, weekly or every few days"),
+    (
+        "F",
+        "Focus on the task, crediting it with the focus sessions",
+    ),
+    ("X", "Archive the task, or the marked tasks"),
+    ("Shift+X", "Archive the done tasks"),
+    ("Shift+A", "Browse the archive"),
+    ("Tab/Shift+Tab", "Switch to the next or previous list"),
+    ("Shift+L", "Pick or create a list"),
+    ("M", "Move the task, or the marked tasks, to another list"),
+    ("/", "Search the titles and notes of the list"),
+    (
+        "S / G / H",
+        "Change the sort, the grouping and whether done tasks show",
+    ),
+    ("Shift+E", "Export the tasks and the focus sessions to CSV"),
+    (
+        "Esc / T",
+        "Clear the search or the marks, then close the task list",
+    ),
+];
+
+/// Lists every key of the task list, which its controls line has no room for
+pub struct HelpPopup {
+    popup_factory: Rc<PopupFactory>,
+    task_opened_on_idx: usize,
+    scroll: u16,
+}
+
+impl HelpPopup {
+    pub fn new(popup_factory: Rc<PopupFactory>, task_opened_on_idx: usize) -> Self {
+        HelpPopup {
+            popup_factory,
+            task_opened_on_idx,
+            scroll: 0,
+        }
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('?') => {
+                return Some(Action::OpenPopup {
+                    popup: self
+                        .popup_factory
+                        .create_task_list_popup(self.task_opened_on_idx),
+                });
+            }
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => {
+                self.scroll = (self.scroll + 1).min(TASK_LIST_KEYS.len() as u16 - 1);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl Popup for HelpPopup {
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect, theme: &Theme) {
+        let block = Block::bordered()
+            .border_style(theme.border_style)
+            .style(theme.background_style);
+
+        let popup_area: Rect = popup_area(area, 60, 80);
+        frame.render_widget(Clear, popup_area);
+        let inner_area: Rect = block.inner(popup_area);
+        let inner_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+            ])
+            .split(inner_area);
+
+        let title_text: Text = Text::styled(
+            "Keys of the task list",
+            theme.text_style().add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let title_paragraph: Paragraph = Paragraph::new(title_text).centered();
+
+        let key_width = TASK_LIST_KEYS
+            .iter()
+            .map(|(key, _)| key.len())
+            .max()
+            .unwrap_or(0);
+        let key_lines: Vec<Line> = TASK_LIST_KEYS
+            .iter()
+            .map(|(key, description)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>width$}  ", key, width = key_width),
+                        theme.text_style().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(*description, theme.text_style()),
+                ])
+            })
+            .collect();
+        let keys_paragraph: Paragraph = Paragraph::new(key_lines).scroll((self.scroll, 0));
+
+        let controls_text: Text = Text::styled(
+            "Controls: Up/Down to scroll, Esc/? to go back to the task list",
+            theme.text_style(),
+        )
+        .centered();
+        let controls_paragraph: Paragraph = Paragraph::new(controls_text).centered();
+
+        frame.render_widget(block, popup_area);
+        frame.render_widget(title_paragraph, inner_layout[0]);
+        frame.render_widget(keys_paragraph, inner_layout[1]);
+        frame.render_widget(controls_paragraph, inner_layout[2]);
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event),
+
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Style,
+    text::Text,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{
+    app::{Action, Popup},
+    theme::Theme,
+    utils::popup_area,
+};
+
+/// Single-line text prompt, turning the typed value into an action on Enter
+pub struct InputPopup {
+    title: String,
+    field_name: String,
+    current_value: String,
+    on_submit: Box<dyn Fn(String) -> Action>,
+    on_cancel: Box<dyn Fn() -> Action>,
+}
+
+impl InputPopup {
+    pub fn new(
+        title: String,
+        field_name: String,
+        initial_value: String,
+        on_submit: Box<dyn Fn(String) -> Action>,
+        on_cancel: Box<dyn Fn() -> Action>,
+    ) -> Self {
+        InputPopup {
+            title,
+            field_name,
+            current_value: initial_value,
+            on_submit,
+            on_cancel,
+        }
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
+        match key_event.code {
+            KeyCode::Esc => Some((self.on_cancel)()),
+            KeyCode::Enter => Some((self.on_submit)(self.current_value.clone())),
+            KeyCode::Backspace => {
+                self.current_value.pop();
+                None
+            }
+            KeyCode::Char(c) => {
+                self.current_value.push(c);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Popup for InputPopup {
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect, theme: &Theme) {
+        let block = Block::bordered()
+            .border_style(theme.border_style)
+            .style(theme.background_style);
+
+        let popup_area: Rect = popup_area(area, 30, 30);
+        frame.render_widget(Clear, popup_area);
+        let inner_area: Rect = block.inner(popup_area);
+        let inner_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(3),
+                Constraint::Fill(1),
+            ])
+            .split(inner_area);
+
+        let title_text: Text = Text::styled(self.title.clone(), theme.text_style()).centered();
+        let title_paragraphe: Paragraph = Paragraph::new(title_text).centered();
+
+        let input_chunks = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Percentage(90),
+            Constraint::Fill(1),
+        ])
+        .split(inner_layout[2]);
+
+        let input_block = Block::default()
+            .title(self.field_name.clone())
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(Style::default().reversed());
+        let input_paragraph: Paragraph = Paragraph::new(self.current_value.clone())
+            .block(input_block)
+            .centered();
+
+        frame.render_widget(block, popup_area);
+        frame.render_widget(title_paragraphe, inner_layout[0]);
+        frame.render_widget(input_paragraph, input_chunks[1]);
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event),
+
+            _ => None,
+        }
+    }
+
+    fn is_typing(&self) -> bool {
+        true
+    }
+}
@@ -1,3 +1,9 @@
 pub mod add_task;
+pub mod archive;
+pub mod conflict;
 pub mod error;
+pub mod help;
+pub mod input;
+pub mod set_status;
+pub mod task_detail;
 pub mod task_list;
@@ -0,0 +1,124 @@
+use std::rc::Rc;
+
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Style,
+    text::Text,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use uuid::Uuid;
+
+use crate::{
+    app::{Action, Popup},
+    popup_factory::PopupFactory,
+    popups::task_list::TaskStatus,
+    theme::Theme,
+    utils::popup_area,
+};
+
+/// Picks one status to give to several tasks at once
+pub struct SetStatusPopup {
+    popup_factory: Rc<PopupFactory>,
+    task_uuids: Vec<Uuid>,
+    task_opened_on_idx: usize,
+    current_status: TaskStatus,
+}
+
+impl SetStatusPopup {
+    pub fn new(
+        popup_factory: Rc<PopupFactory>,
+        task_uuids: Vec<Uuid>,
+        task_opened_on_idx: usize,
+    ) -> Self {
+        SetStatusPopup {
+            popup_factory,
+            task_uuids,
+            task_opened_on_idx,
+            current_status: TaskStatus::done(),
+        }
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
+        match key_event.code {
+            KeyCode::Esc => Some(Action::OpenPopup {
+                popup: self
+                    .popup_factory
+                    .create_task_list_popup(self.task_opened_on_idx),
+            }),
+            KeyCode::Enter => Some(Action::SetTasksStatus {
+                task_uuids: self.task_uuids.clone(),
+                status: self.current_status.clone(),
+            }),
+            KeyCode::Up => {
+                self.current_status = self.current_status.previous();
+                None
+            }
+            KeyCode::Down => {
+                self.current_status = self.current_status.next();
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Popup for SetStatusPopup {
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect, theme: &Theme) {
+        let block = Block::bordered()
+            .border_style(theme.border_style)
+            .style(theme.background_style);
+
+        let popup_area: Rect = popup_area(area, 30, 30);
+        frame.render_widget(Clear, popup_area);
+        let inner_area: Rect = block.inner(popup_area);
+        let inner_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(3),
+                Constraint::Fill(1),
+            ])
+            .split(inner_area);
+
+        let title_text: Text = Text::styled(
+            format!("Set the status of {} task(s)", self.task_uuids.len()),
+            theme.text_style(),
+        )
+        .centered();
+        let title_paragraphe: Paragraph = Paragraph::new(title_text).centered();
+
+        let status_chunks = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Percentage(45),
+            Constraint::Fill(1),
+        ])
+        .split(inner_layout[2]);
+
+        let status_block = Block::default()
+            .title("Status")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(Style::default().reversed());
+        let status_paragraph: Paragraph = Paragraph::new(format!(
+            "{} {}",
+            self.current_status.emoji(),
+            self.current_status
+        ))
+        .block(status_block)
+        .centered();
+
+        frame.render_widget(block, popup_area);
+        frame.render_widget(title_paragraphe, inner_layout[0]);
+        frame.render_widget(status_paragraph, status_chunks[1]);
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event),
+
+            _ => None,
+        }
+    }
+}
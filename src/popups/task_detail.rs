@@ -0,0 +1,331 @@
+use std::{cell::RefCell, rc::Rc};
+
+use chrono::{DateTime, Local, Utc};
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+use uuid::Uuid;
+
+use crate::{
+    app::{Action, Popup},
+    popup_factory::PopupFactory,
+    popups::task_list::{Task, list_display_name},
+    storage::TaskManager,
+    theme::Theme,
+    utils::{format_duration, popup_area},
+};
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Every field of one task, its notes and the statuses it went through
+pub struct TaskDetailPopup {
+    popup_factory: Rc<PopupFactory>,
+    task_manager: Rc<RefCell<TaskManager>>,
+    task_uuid: Uuid,
+    task_opened_on_idx: usize,
+    /// First line of the notes shown
+    notes_scroll: u16,
+    /// Notes being typed, saved once editing ends
+    edited_notes: Option<String>,
+}
+
+impl TaskDetailPopup {
+    pub fn new(
+        popup_factory: Rc<PopupFactory>,
+        task_manager: Rc<RefCell<TaskManager>>,
+        task_uuid: Uuid,
+        task_opened_on_idx: usize,
+    ) -> Self {
+        TaskDetailPopup {
+            popup_factory,
+            task_manager,
+            task_uuid,
+            task_opened_on_idx,
+            notes_scroll: 0,
+            edited_notes: None,
+        }
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
+        if let Some(edited_notes) = &mut self.edited_notes {
+            match key_event.code {
+                KeyCode::Esc => {
+                    return Some(Action::SetTaskNotes {
+                        task_uuid: self.task_uuid,
+                        notes: self.edited_notes.take().unwrap_or_default(),
+                        task_opened_on_idx: self.task_opened_on_idx,
+                    });
+                }
+                KeyCode::Enter => edited_notes.push('\n'),
+                KeyCode::Tab => edited_notes.push('\t'),
+                KeyCode::Backspace => {
+                    edited_notes.pop();
+                }
+                KeyCode::Char(c) => edited_notes.push(c),
+                _ => {}
+            }
+            return None;
+        }
+
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Enter => {
+                return Some(Action::OpenPopup {
+                    popup: self
+                        .popup_factory
+                        .create_task_list_popup(self.task_opened_on_idx),
+                });
+            }
+            KeyCode::Char('n') => {
+                self.edited_notes = self
+                    .task_manager
+                    .borrow()
+                    .tasks
+                    .iter()
+                    .find(|task| task.uuid == self.task_uuid)
+                    .map(|task| task.notes.clone());
+            }
+            KeyCode::Char('N') => {
+                return Some(Action::EditTaskNotesInEditor {
+                    task_uuid: self.task_uuid,
+                    task_opened_on_idx: self.task_opened_on_idx,
+                });
+            }
+            KeyCode::Up => self.notes_scroll = self.notes_scroll.saturating_sub(1),
+            KeyCode::Down => self.notes_scroll = self.notes_scroll.saturating_add(1),
+            KeyCode::PageUp => self.notes_scroll = self.notes_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.notes_scroll = self.notes_scroll.saturating_add(10),
+            _ => {}
+        }
+        None
+    }
+
+    fn field_lines<'a>(task: &Task, task_manager: &TaskManager, theme: &Theme) -> Vec<Line<'a>> {
+        let format_datetime = |datetime: Option<DateTime<Utc>>| {
+            datetime
+                .map(|datetime| {
+                    datetime
+                        .with_timezone(&Local)
+                        .format(DATETIME_FORMAT)
+                        .to_string()
+                })
+                .unwrap_or_else(|| String::from("-"))
+        };
+        let fields: Vec<(&str, String)> = vec![
+            ("Title", task.title.clone()),
+            ("Status", format!("{} {}", task.status.emoji(), task.status)),
+            ("List", list_display_name(&task.list).to_string()),
+            (
+                "Priority",
+                task.priority
+                    .map(String::from)
+                    .unwrap_or_else(|| String::from("-")),
+            ),
+            (
+                "Tags",
+                task.tags
+                    .iter()
+                    .map(|tag| format!("#{}", tag))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            ),
+            (
+                "Estimate",
+                task.estimate
+                    .map(|estimate| format!("{} focus session(s)", estimate))
+                    .unwrap_or_else(|| String::from("-")),
+            ),
+            (
+                "Due",
+                task.due_date
+                    .map(|due_date| due_date.format("%a %Y-%m-%d").to_string())
+                    .unwrap_or_else(|| String::from("-")),
+            ),
+            (
+                "Repeat",
+                task.recurrence
+                    .as_ref()
+                    .map(|recurrence| recurrence.to_string())
+                    .unwrap_or_else(|| String::from("never")),
+            ),
+            (
+                "Blocked by",
+                if task.blocked_by.is_empty() {
+                    String::from("-")
+                } else {
+                    // Blockers that were deleted or archived no longer block
+                    task_manager
+                        .tasks
+                        .iter()
+                        .filter(|blocker| task.blocked_by.contains(&blocker.uuid))
+                        .map(|blocker| format!("{} {}", blocker.status.emoji(), blocker.title))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                },
+            ),
+            ("Created", format_datetime(task.created_at)),
+            ("Updated", format_datetime(task.updated_at)),
+            ("Completed", format_datetime(task.completed_at)),
+            (
+                "Cycle time",
+                task.cycle_time()
+                    .map(format_duration)
+                    .unwrap_or_else(|| String::from("-")),
+            ),
+        ];
+
+        fields
+            .into_iter()
+            .map(|(name, value)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>11}: ", name),
+                        theme.text_style().add_modifier(Modifier::DIM),
+                    ),
+                    Span::styled(value, theme.text_style()),
+                ])
+            })
+            .collect()
+    }
+
+    /// Status changes, latest first
+    fn activity_lines<'a>(task: &Task, theme: &Theme) -> Vec<Line<'a>> {
+        if task.status_history.is_empty() {
+            return vec![Line::styled(
+                "No status change recorded",
+                theme.text_style().add_modifier(Modifier::DIM),
+            )];
+        }
+        task.status_history
+            .iter()
+            .rev()
+            .map(|status_change| {
+                Line::styled(
+                    format!(
+                        "{}  {} -> {}",
+                        status_change
+                            .at
+                            .with_timezone(&Local)
+                            .format(DATETIME_FORMAT),
+                        status_change.from,
+                        status_change.to
+                    ),
+                    theme.text_style(),
+                )
+            })
+            .collect()
+    }
+}
+
+impl Popup for TaskDetailPopup {
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect, theme: &Theme) {
+        let block = Block::bordered()
+            .border_style(theme.border_style)
+            .style(theme.background_style);
+
+        let popup_area: Rect = popup_area(area, 60, 60);
+        frame.render_widget(Clear, popup_area);
+        let inner_area: Rect = block.inner(popup_area);
+        let inner_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Length(14),
+                Constraint::Length(1),
+                Constraint::Fill(2),
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(2),
+            ])
+            .split(inner_area);
+
+        let title_text: Text = Text::styled("Task details", theme.text_style()).centered();
+        let title_paragraphe: Paragraph = Paragraph::new(title_text).centered();
+
+        let task_manager = self.task_manager.borrow();
+        let Some(task) = task_manager
+            .tasks
+            .iter()
+            .find(|task| task.uuid == self.task_uuid)
+        else {
+            // Deleted by a change made elsewhere
+            let missing_text: Text =
+                Text::styled("This task no longer exists", theme.error_text_style()).centered();
+            frame.render_widget(block, popup_area);
+            frame.render_widget(title_paragraphe, inner_layout[0]);
+            frame.render_widget(Paragraph::new(missing_text), inner_layout[1]);
+            return;
+        };
+        let fields_paragraph: Paragraph =
+            Paragraph::new(Self::field_lines(task, &task_manager, theme))
+                .wrap(Wrap { trim: false });
+
+        let notes_title: Line =
+            Line::styled("Notes", theme.text_style().add_modifier(Modifier::BOLD));
+        let notes_area: Rect = inner_layout[3];
+        let notes_paragraph: Paragraph = match &self.edited_notes {
+            // Follows the end of the notes, where the text is typed
+            Some(edited_notes) => {
+                let line_count = edited_notes.split('\n').count() as u16;
+                Paragraph::new(Text::styled(
+                    format!("{}▏", edited_notes),
+                    theme.marked_text_style(),
+                ))
+                .wrap(Wrap { trim: false })
+                .scroll((line_count.saturating_sub(notes_area.height), 0))
+            }
+            None if task.notes.is_empty() => Paragraph::new(Text::styled(
+                "No notes, N to write some",
+                theme.text_style().add_modifier(Modifier::DIM),
+            )),
+            None => {
+                let line_count = task.notes.lines().count() as u16;
+                self.notes_scroll = self
+                    .notes_scroll
+                    .min(line_count.saturating_sub(notes_area.height));
+                Paragraph::new(Text::styled(task.notes.clone(), theme.text_style()))
+                    .wrap(Wrap { trim: false })
+                    .scroll((self.notes_scroll, 0))
+            }
+        };
+
+        let activity_title: Line =
+            Line::styled("Activity", theme.text_style().add_modifier(Modifier::BOLD));
+        let activity_paragraph: Paragraph = Paragraph::new(Self::activity_lines(task, theme));
+        drop(task_manager);
+
+        let controls_content = if self.edited_notes.is_some() {
+            "Controls: type the notes, Enter for a new line, Esc to save them"
+        } else {
+            "Controls: N to edit the notes, Shift+N to edit them in $EDITOR, Up/Down to scroll them, Esc/Enter to go back to the task list"
+        };
+        let controls_text: Text = Text::styled(controls_content, theme.text_style()).centered();
+        let controls_paragraph: Paragraph = Paragraph::new(controls_text)
+            .wrap(Wrap { trim: true })
+            .centered();
+
+        frame.render_widget(block, popup_area);
+        frame.render_widget(title_paragraphe, inner_layout[0]);
+        frame.render_widget(fields_paragraph, inner_layout[1]);
+        frame.render_widget(notes_title, inner_layout[2]);
+        frame.render_widget(notes_paragraph, notes_area);
+        frame.render_widget(activity_title, inner_layout[4]);
+        frame.render_widget(activity_paragraph, inner_layout[5]);
+        frame.render_widget(controls_paragraph, inner_layout[6]);
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event),
+
+            _ => None,
+        }
+    }
+
+    fn is_typing(&self) -> bool {
+        self.edited_notes.is_some()
+    }
+}
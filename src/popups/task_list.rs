@@ -1,11 +1,12 @@
-use std::{cell::RefCell, fmt::Display, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, collections::HashSet, fmt::Display, rc::Rc};
 
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
 use ratatui::{
-    crossterm::event::{Event, KeyCode, KeyEvent},
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
     layout::{Constraint, Layout, Rect},
-    style::Style,
-    text::{Line, Text},
-    widgets::{Block, Clear, List, ListItem, ListState, Paragraph},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -13,150 +14,1012 @@ use uuid::Uuid;
 use crate::{
     app::{Action, Popup},
     popup_factory::PopupFactory,
+    recurrence::Recurrence,
+    sessions::Session,
+    settings::SettingsManager,
     storage::{SaveTaskError, TaskManager},
     theme::Theme,
-    utils::popup_area,
+    utils::{CountdownType, format_duration, popup_area},
+    workflow::workflow,
 };
 
+/// Name of one of the statuses of the workflow, saved as is in the task files
+///
+/// Tasks may keep a status the workflow no longer has, which then sorts last and does not
+/// count as done.
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
-pub enum TaskStatus {
-    Done,
-    Ongoing,
-    Todo,
-}
+#[serde(transparent)]
+pub struct TaskStatus(String);
 
 impl TaskStatus {
+    pub fn new(name: &str) -> Self {
+        TaskStatus(name.to_string())
+    }
+
+    /// First status of the workflow, given to new tasks
+    pub fn initial() -> Self {
+        TaskStatus::new(&workflow().statuses[0].name)
+    }
+
+    /// First status of the workflow counting as done, given to completed tasks
+    pub fn done() -> Self {
+        let workflow = workflow();
+        workflow
+            .statuses
+            .iter()
+            .find(|status| status.done)
+            .map(|status| TaskStatus::new(&status.name))
+            .unwrap_or_else(TaskStatus::initial)
+    }
+
+    /// First status after the initial one that is not done, for formats that only tell apart
+    /// tasks to do, started and done
+    pub fn started() -> Self {
+        workflow()
+            .statuses
+            .iter()
+            .skip(1)
+            .find(|status| !status.done)
+            .map(|status| TaskStatus::new(&status.name))
+            .unwrap_or_else(TaskStatus::initial)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_done(&self) -> bool {
+        workflow().status(&self.0).is_some_and(|status| status.done)
+    }
+
+    /// Following status in the workflow, the last one leading back to the first
     pub fn next(&self) -> TaskStatus {
-        match self {
-            TaskStatus::Done => TaskStatus::Ongoing,
-            TaskStatus::Ongoing => TaskStatus::Todo,
-            TaskStatus::Todo => TaskStatus::Done,
-        }
+        let statuses = &workflow().statuses;
+        let next_idx = match workflow().position(&self.0) {
+            Some(idx) => (idx + 1) % statuses.len(),
+            None => 0,
+        };
+        TaskStatus::new(&statuses[next_idx].name)
     }
 
     pub fn previous(&self) -> TaskStatus {
-        match self {
-            TaskStatus::Done => TaskStatus::Todo,
-            TaskStatus::Ongoing => TaskStatus::Done,
-            TaskStatus::Todo => TaskStatus::Ongoing,
-        }
+        let statuses = &workflow().statuses;
+        let previous_idx = match workflow().position(&self.0) {
+            Some(idx) => (idx + statuses.len() - 1) % statuses.len(),
+            None => 0,
+        };
+        TaskStatus::new(&statuses[previous_idx].name)
     }
 
     pub fn emoji(&self) -> String {
-        match self {
-            TaskStatus::Done => String::from('✅'),
-            TaskStatus::Ongoing => String::from('⏳'),
-            TaskStatus::Todo => String::from('❌'),
-        }
+        workflow()
+            .status(&self.0)
+            .map(|status| status.glyph.clone())
+            .unwrap_or_else(|| String::from('❔'))
+    }
+
+    pub fn color(&self) -> Option<Color> {
+        workflow().status(&self.0)?.color()
+    }
+
+    /// Position of the status when tasks are sorted or grouped by status
+    fn rank(&self) -> usize {
+        workflow()
+            .position(&self.0)
+            .unwrap_or(workflow().statuses.len())
+    }
+}
+
+impl Default for TaskStatus {
+    fn default() -> Self {
+        TaskStatus::initial()
     }
 }
 
 impl Display for TaskStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            TaskStatus::Done => write!(f, "Done"),
-            TaskStatus::Ongoing => write!(f, "Ongoing"),
-            TaskStatus::Todo => write!(f, "Todo"),
-        }
+        write!(f, "{}", self.0)
     }
 }
 
-// TODO: use an id / uuid ?
-#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Task {
     pub uuid: Uuid,
     pub title: String,
     pub status: TaskStatus,
+    /// `None` for tasks saved before creation dates were recorded
+    pub created_at: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    /// From `A`, the most important, to `Z` as in todo.txt
+    pub priority: Option<char>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Name of the list holding the task, `None` for the default list
+    pub list: Option<String>,
+    /// Makes the task come back once done, instead of staying done
+    pub recurrence: Option<Recurrence>,
+    /// Every time a recurring task was completed, oldest first
+    pub completion_history: Vec<DateTime<Utc>>,
+    /// Last time the task was changed, `None` for tasks not changed since this was recorded
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Status changes of the task, oldest first
+    pub status_history: Vec<StatusChange>,
+    /// Free text of any number of lines
+    pub notes: String,
+    /// Tasks to get done before this one can be worked on
+    pub blocked_by: Vec<Uuid>,
+    /// Number of focus sessions the task is expected to take
+    pub estimate: Option<u32>,
+    pub due_date: Option<NaiveDate>,
+}
+
+/// A task going from one status to another
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct StatusChange {
+    pub at: DateTime<Utc>,
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+}
+
+/// Shown for the list of tasks that were not put in a named list
+pub const DEFAULT_LIST_NAME: &str = "Inbox";
+
+/// Name to show for a list, `None` being the default list
+pub fn list_display_name(list: &Option<String>) -> &str {
+    list.as_deref().unwrap_or(DEFAULT_LIST_NAME)
+}
+
+/// Reads a list name typed by the user, the default list also answering to its displayed name
+pub fn parse_list_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() || name == DEFAULT_LIST_NAME {
+        None
+    } else {
+        Some(name.to_string())
+    }
 }
 
 impl Task {
-    fn get_list_item<'a>(&self) -> ListItem<'a> {
-        let content: String = format!("{} {}", self.status.emoji(), self.title);
-        let line: Line = Line::from(content).centered();
-        ListItem::new(line)
+    /// Item for the task in a list, blocked tasks being dimmed and locked
+    pub fn get_list_item<'a>(&self, is_blocked: bool) -> ListItem<'a> {
+        let mut status_style = Style::default();
+        if let Some(status_color) = self.status.color() {
+            status_style = status_style.fg(status_color);
+        }
+        let mut content: String = String::from(" ");
+        if is_blocked {
+            content.push_str("🔒 ");
+        }
+        if let Some(priority) = self.priority {
+            content.push_str(&format!("({}) ", priority));
+        }
+        content.push_str(&self.title);
+        for tag in &self.tags {
+            content.push_str(&format!(" #{}", tag));
+        }
+        if let Some(estimate) = self.estimate {
+            content.push_str(&format!(" ~{}", estimate));
+        }
+        if let Some(due_date) = self.due_date {
+            content.push_str(&format!(" 📅 {}", due_date.format("%a %Y-%m-%d")));
+        }
+        if let Some(recurrence) = &self.recurrence {
+            let streak = recurrence.streak(&self.completion_dates(), Local::now().date_naive());
+            content.push_str(&format!(" 🔁 {} 🔥{}", recurrence, streak));
+        }
+        let line: Line = Line::from(vec![
+            Span::styled(self.status.emoji(), status_style),
+            Span::raw(content),
+        ])
+        .centered();
+        if is_blocked {
+            ListItem::new(line.patch_style(Modifier::DIM))
+        } else {
+            ListItem::new(line)
+        }
+    }
+
+    /// Local days the task was completed on
+    fn completion_dates(&self) -> Vec<NaiveDate> {
+        self.completion_history
+            .iter()
+            .map(|completed_at| completed_at.with_timezone(&Local).date_naive())
+            .collect()
+    }
+
+    /// Time between the task being started, out of its first status, and being completed
+    pub fn cycle_time(&self) -> Option<TimeDelta> {
+        let is_started =
+            |status: &TaskStatus| !status.is_done() && *status != TaskStatus::initial();
+        let completed_at = self.completed_at.filter(|_| self.status.is_done())?;
+        // A task created already started counts from its creation
+        let started_at = match self.status_history.first() {
+            Some(status_change) if is_started(&status_change.from) => self.created_at,
+            _ => self
+                .status_history
+                .iter()
+                .find(|status_change| is_started(&status_change.to))
+                .map(|status_change| status_change.at),
+        }?;
+        (completed_at >= started_at).then(|| completed_at - started_at)
+    }
+
+    /// Whether a recurring task is done for an occurrence that has passed, its next one being due
+    pub fn is_due_again(&self, today: NaiveDate) -> bool {
+        match (&self.recurrence, &self.status, self.completed_at) {
+            (Some(recurrence), status, Some(completed_at)) if status.is_done() => {
+                recurrence.next_due_date(completed_at.with_timezone(&Local).date_naive()) <= today
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum SortMode {
+    #[default]
+    Manual,
+    Status,
+    Title,
+    CreationDate,
+    Priority,
+}
+
+impl SortMode {
+    pub fn next(&self) -> SortMode {
+        match self {
+            SortMode::Manual => SortMode::Status,
+            SortMode::Status => SortMode::Title,
+            SortMode::Title => SortMode::CreationDate,
+            SortMode::CreationDate => SortMode::Priority,
+            SortMode::Priority => SortMode::Manual,
+        }
+    }
+
+    fn compare(&self, first: &Task, second: &Task) -> Ordering {
+        match self {
+            SortMode::Manual => Ordering::Equal,
+            SortMode::Status => first.status.rank().cmp(&second.status.rank()),
+            SortMode::Title => first.title.to_lowercase().cmp(&second.title.to_lowercase()),
+            SortMode::CreationDate => first.created_at.cmp(&second.created_at),
+            // Tasks without a priority come last
+            SortMode::Priority => match (first.priority, second.priority) {
+                (Some(first_priority), Some(second_priority)) => {
+                    first_priority.cmp(&second_priority)
+                }
+                (first_priority, second_priority) => {
+                    second_priority.is_some().cmp(&first_priority.is_some())
+                }
+            },
+        }
+    }
+}
+
+impl Display for SortMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortMode::Manual => write!(f, "Manual"),
+            SortMode::Status => write!(f, "Status"),
+            SortMode::Title => write!(f, "Title"),
+            SortMode::CreationDate => write!(f, "Creation date"),
+            SortMode::Priority => write!(f, "Priority"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum DoneVisibility {
+    #[default]
+    Shown,
+    Sunk,
+    Hidden,
+}
+
+impl DoneVisibility {
+    pub fn next(&self) -> DoneVisibility {
+        match self {
+            DoneVisibility::Shown => DoneVisibility::Sunk,
+            DoneVisibility::Sunk => DoneVisibility::Hidden,
+            DoneVisibility::Hidden => DoneVisibility::Shown,
+        }
+    }
+}
+
+impl Display for DoneVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DoneVisibility::Shown => write!(f, "shown"),
+            DoneVisibility::Sunk => write!(f, "at the bottom"),
+            DoneVisibility::Hidden => write!(f, "hidden"),
+        }
+    }
+}
+
+/// How the task list is ordered and filtered, remembered between runs through the settings file
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TaskListView {
+    pub sort_mode: SortMode,
+    pub group_by_status: bool,
+    pub done_visibility: DoneVisibility,
+}
+
+impl TaskListView {
+    /// Returns the rows to display for a list, keeping the tasks whose title or notes contain
+    /// `search_query` whatever the case, each task row holding the index of the task in `tasks`
+    fn rows(&self, tasks: &[Task], list: &Option<String>, search_query: &str) -> Vec<TaskListRow> {
+        let search_query = search_query.to_lowercase();
+        let mut task_indices: Vec<usize> = (0..tasks.len())
+            .filter(|idx| tasks[*idx].list == *list)
+            .filter(|idx| {
+                tasks[*idx].title.to_lowercase().contains(&search_query)
+                    || tasks[*idx].notes.to_lowercase().contains(&search_query)
+            })
+            .filter(|idx| {
+                self.done_visibility != DoneVisibility::Hidden || !tasks[*idx].status.is_done()
+            })
+            .collect();
+
+        task_indices.sort_by(|first, second| {
+            let (first, second) = (&tasks[*first], &tasks[*second]);
+            let sink_done =
+                |task: &Task| self.done_visibility == DoneVisibility::Sunk && task.status.is_done();
+            let group_rank = |task: &Task| {
+                if self.group_by_status {
+                    task.status.rank()
+                } else {
+                    0
+                }
+            };
+
+            sink_done(first)
+                .cmp(&sink_done(second))
+                .then(group_rank(first).cmp(&group_rank(second)))
+                .then(self.sort_mode.compare(first, second))
+        });
+
+        let mut rows: Vec<TaskListRow> = Vec::new();
+        for task_idx in task_indices {
+            let status = &tasks[task_idx].status;
+            let starts_group = match rows.last() {
+                Some(TaskListRow::Task(previous_idx)) => tasks[*previous_idx].status != *status,
+                Some(TaskListRow::Header(_)) => false,
+                None => true,
+            };
+            if self.group_by_status && starts_group {
+                rows.push(TaskListRow::Header(status.clone()));
+            }
+            rows.push(TaskListRow::Task(task_idx));
+        }
+        rows
     }
 }
 
+enum TaskListRow {
+    Header(TaskStatus),
+    Task(usize),
+}
+
 pub struct TaskListPopup {
     popup_factory: Rc<PopupFactory>,
     task_manager: Rc<RefCell<TaskManager>>,
+    settings_manager: Rc<RefCell<SettingsManager>>,
     list_state: ListState,
+    /// Tasks picked with Space or a range, which bulk actions apply to instead of the cursor
+    marked_task_uuids: HashSet<Uuid>,
+    range_anchor_uuid: Option<Uuid>,
+    /// Text the tasks shown are filtered on
+    search_query: String,
+    /// Whether keys go to `search_query`
+    is_searching: bool,
 }
 
 impl TaskListPopup {
     pub fn new(
         popup_factory: Rc<PopupFactory>,
         task_manager: Rc<RefCell<TaskManager>>,
+        settings_manager: Rc<RefCell<SettingsManager>>,
         selected_task_idx: usize,
     ) -> Self {
-        let mut list_state = ListState::default();
-        let task_count = task_manager.borrow().tasks.len();
-        if task_count > 0 {
-            list_state.select(Some(selected_task_idx)); // Select first item by default
-        }
-
-        TaskListPopup {
+        let mut task_list_popup = TaskListPopup {
             popup_factory,
             task_manager,
-            list_state: list_state,
+            settings_manager,
+            list_state: ListState::default(),
+            marked_task_uuids: HashSet::new(),
+            range_anchor_uuid: None,
+            search_query: String::new(),
+            is_searching: false,
+        };
+        task_list_popup.select_task(selected_task_idx);
+        task_list_popup
+    }
+
+    fn view(&self) -> TaskListView {
+        self.settings_manager.borrow().settings.task_list_view
+    }
+
+    fn rows(&self) -> Vec<TaskListRow> {
+        let task_manager = self.task_manager.borrow();
+        self.view().rows(
+            &task_manager.tasks,
+            &task_manager.selected_list,
+            &self.search_query,
+        )
+    }
+
+    /// Shows the next or previous list, in the order of `TaskManager::list_names`
+    fn cycle_list(&self, forward: bool) -> Option<Action> {
+        let task_manager = self.task_manager.borrow();
+        let list_names = task_manager.list_names();
+        let selected_idx = list_names
+            .iter()
+            .position(|list| *list == task_manager.selected_list)
+            .unwrap_or(0);
+        let new_selected_idx = if forward {
+            (selected_idx + 1) % list_names.len()
+        } else {
+            (selected_idx + list_names.len() - 1) % list_names.len()
+        };
+        Some(Action::SelectTaskList {
+            list: list_names[new_selected_idx].clone(),
+        })
+    }
+
+    /// Tasks done out of the tasks of the selected list, the focus time spent on them and the
+    /// average time from starting to completing them
+    fn list_stats(&self) -> String {
+        let task_manager = self.task_manager.borrow();
+        let list_tasks: Vec<&Task> = task_manager
+            .tasks
+            .iter()
+            .filter(|task| task.list == task_manager.selected_list)
+            .collect();
+        let done_count = list_tasks
+            .iter()
+            .filter(|task| task.status.is_done())
+            .count();
+
+        // Archived tasks keep the sessions spent on them
+        let list_task_uuids: HashSet<Uuid> = task_manager
+            .tasks
+            .iter()
+            .chain(task_manager.archived_tasks.iter())
+            .filter(|task| task.list == task_manager.selected_list)
+            .map(|task| task.uuid)
+            .collect();
+        let session_manager = self.popup_factory.session_manager.borrow();
+        let list_sessions: Vec<&Session> = session_manager
+            .sessions
+            .iter()
+            .filter(|session| {
+                session.phase == CountdownType::Work
+                    && session
+                        .task_uuid
+                        .is_some_and(|task_uuid| list_task_uuids.contains(&task_uuid))
+            })
+            .collect();
+        let focus_minutes: u64 = list_sessions
+            .iter()
+            .map(|session| session.duration_seconds)
+            .sum::<u64>()
+            / 60;
+
+        let cycle_times: Vec<TimeDelta> = task_manager
+            .tasks
+            .iter()
+            .chain(task_manager.archived_tasks.iter())
+            .filter(|task| task.list == task_manager.selected_list)
+            .filter_map(Task::cycle_time)
+            .collect();
+
+        let mut stats = format!(
+            "{}/{} done | {} focus session(s), {}h{:02}m",
+            done_count,
+            list_tasks.len(),
+            list_sessions.len(),
+            focus_minutes / 60,
+            focus_minutes % 60
+        );
+        if !cycle_times.is_empty() {
+            let average_cycle_time =
+                cycle_times.iter().sum::<TimeDelta>() / cycle_times.len() as i32;
+            stats.push_str(&format!(
+                " | avg cycle {}",
+                format_duration(average_cycle_time)
+            ));
         }
+        stats
+    }
+
+    /// Index in `TaskManager::tasks` of the task under the cursor
+    fn selected_task_idx(&self) -> Option<usize> {
+        let selected_row = self.list_state.selected()?;
+        match self.rows().get(selected_row) {
+            Some(TaskListRow::Task(task_idx)) => Some(*task_idx),
+            _ => None,
+        }
+    }
+
+    /// Marked tasks in display order, or the task under the cursor when none is marked
+    fn target_task_uuids(&self) -> Vec<Uuid> {
+        let task_manager = self.task_manager.borrow();
+        if self.marked_task_uuids.is_empty() {
+            return self
+                .selected_task_idx()
+                .map(|task_idx| vec![task_manager.tasks[task_idx].uuid])
+                .unwrap_or_default();
+        }
+
+        self.rows()
+            .iter()
+            .filter_map(|row| match row {
+                TaskListRow::Task(task_idx) => Some(task_manager.tasks[*task_idx].uuid),
+                TaskListRow::Header(_) => None,
+            })
+            .filter(|task_uuid| self.marked_task_uuids.contains(task_uuid))
+            .collect()
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some(selected_task_idx) = self.selected_task_idx() {
+            let selected_task_uuid = self.task_manager.borrow().tasks[selected_task_idx].uuid;
+            if !self.marked_task_uuids.remove(&selected_task_uuid) {
+                self.marked_task_uuids.insert(selected_task_uuid);
+            }
+        }
+    }
+
+    /// Starts a range on the cursor, or marks every task between the start of the range and the cursor
+    fn mark_range(&mut self) {
+        let (Some(selected_row), Some(selected_task_idx)) =
+            (self.list_state.selected(), self.selected_task_idx())
+        else {
+            return;
+        };
+        let task_manager = self.task_manager.borrow();
+
+        let Some(range_anchor_uuid) = self.range_anchor_uuid.take() else {
+            self.range_anchor_uuid = Some(task_manager.tasks[selected_task_idx].uuid);
+            return;
+        };
+        let rows = self.rows();
+        let anchor_row = rows
+            .iter()
+            .position(|row| {
+                matches!(row, TaskListRow::Task(idx) if task_manager.tasks[*idx].uuid == range_anchor_uuid)
+            })
+            .unwrap_or(selected_row);
+
+        for row in &rows[anchor_row.min(selected_row)..=anchor_row.max(selected_row)] {
+            if let TaskListRow::Task(task_idx) = row {
+                self.marked_task_uuids
+                    .insert(task_manager.tasks[*task_idx].uuid);
+            }
+        }
+    }
+
+    /// Moves the cursor onto the given task, or onto the first visible task if it is not displayed
+    fn select_task(&mut self, task_idx: usize) {
+        let rows = self.rows();
+        let selected_row = rows
+            .iter()
+            .position(|row| matches!(row, TaskListRow::Task(idx) if *idx == task_idx))
+            .or_else(|| {
+                rows.iter()
+                    .position(|row| matches!(row, TaskListRow::Task(_)))
+            });
+        self.list_state.select(selected_row);
+    }
+
+    /// Moves the cursor onto the closest task row at or after `row_idx`, falling back to the last one
+    fn select_row_near(&mut self, row_idx: usize) {
+        let rows = self.rows();
+        let is_task_row = |row: &TaskListRow| matches!(row, TaskListRow::Task(_));
+        let selected_row = rows
+            .iter()
+            .skip(row_idx)
+            .position(is_task_row)
+            .map(|offset| row_idx + offset)
+            .or_else(|| rows.iter().rposition(is_task_row));
+        self.list_state.select(selected_row);
+    }
+
+    fn move_selection(&mut self, forward: bool) {
+        if let Some(selected_row) = self.list_state.selected() {
+            let rows = self.rows();
+            let is_task_row = |row: &TaskListRow| matches!(row, TaskListRow::Task(_));
+            let new_selected_row = if forward {
+                rows.iter()
+                    .skip(selected_row + 1)
+                    .position(is_task_row)
+                    .map(|offset| selected_row + 1 + offset)
+            } else {
+                rows.iter().take(selected_row).rposition(is_task_row)
+            };
+            if let Some(new_selected_row) = new_selected_row {
+                self.list_state.select(Some(new_selected_row));
+            }
+        }
+    }
+
+    fn update_view(&mut self, update: impl FnOnce(&mut TaskListView)) -> Option<Action> {
+        let selected_task_idx = self.selected_task_idx();
+        let mut view = self.view();
+        update(&mut view);
+
+        if let Err(error) = self.settings_manager.borrow_mut().set_task_list_view(view) {
+            return Some(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error.to_string()),
+            });
+        }
+
+        self.select_task(selected_task_idx.unwrap_or(0));
+        None
+    }
+
+    /// Keeps the cursor in place once tasks were taken out of the list, or reports the failure
+    fn after_removal(
+        &mut self,
+        removal_result: Result<(), SaveTaskError>,
+        selected_row: usize,
+    ) -> Option<Action> {
+        match removal_result {
+            Ok(_) => {
+                self.marked_task_uuids.clear();
+                self.select_row_near(selected_row);
+                None
+            }
+            Err(error) => Some(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error.to_string()),
+            }),
+        }
+    }
+
+    /// Swaps the selected task with the one displayed next to it, only in the manual order
+    fn move_selected_task(&mut self, forward: bool) -> Option<Action> {
+        if self.view().sort_mode != SortMode::Manual {
+            return None;
+        }
+        let selected_row = self.list_state.selected()?;
+        let neighbour_row = if forward {
+            selected_row + 1
+        } else {
+            selected_row.checked_sub(1)?
+        };
+        let rows = self.rows();
+        let (Some(TaskListRow::Task(selected_task_idx)), Some(TaskListRow::Task(neighbour_idx))) =
+            (rows.get(selected_row), rows.get(neighbour_row))
+        else {
+            return None;
+        };
+
+        let task_manager = self.task_manager.borrow();
+        let (selected_task_uuid, neighbour_uuid) = (
+            task_manager.tasks[*selected_task_idx].uuid,
+            task_manager.tasks[*neighbour_idx].uuid,
+        );
+        drop(task_manager);
+
+        let swap_result = self
+            .task_manager
+            .borrow_mut()
+            .swap_tasks(selected_task_uuid, neighbour_uuid);
+        match swap_result {
+            Ok(()) => {
+                self.select_task(*neighbour_idx);
+                None
+            }
+            Err(error) => Some(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error.to_string()),
+            }),
+        }
+    }
+
+    /// Moves the marked tasks, or the one under the cursor, to another status
+    fn change_status(&mut self, forward: Option<bool>) -> Option<Action> {
+        let selected_row = self.list_state.selected()?;
+        let target_task_uuids = self.target_task_uuids();
+        let status_result = match forward {
+            Some(forward) => self
+                .task_manager
+                .borrow_mut()
+                .shift_tasks_status(&target_task_uuids, forward),
+            None => self
+                .task_manager
+                .borrow_mut()
+                .set_tasks_status(&target_task_uuids, TaskStatus::done()),
+        };
+
+        match status_result {
+            Ok(completed_task_uuids) => {
+                // The status change can move the task when sorting or grouping by status
+                let task_manager = self.task_manager.borrow();
+                let task_row = self.rows().iter().position(|row| {
+                    matches!(row, TaskListRow::Task(idx) if target_task_uuids.first() == Some(&task_manager.tasks[*idx].uuid))
+                });
+                drop(task_manager);
+                match task_row {
+                    Some(task_row) => self.list_state.select(Some(task_row)),
+                    None => self.select_row_near(selected_row),
+                }
+                Some(Action::TasksCompleted {
+                    task_uuids: completed_task_uuids,
+                })
+            }
+            Err(error) => Some(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error.to_string()),
+            }),
+        }
+    }
+
+    /// Makes the task under the cursor blocked by the marked tasks, or unblocks it when none
+    /// is marked
+    fn block_selected_task(&mut self) -> Option<Action> {
+        let selected_task_idx = self.selected_task_idx()?;
+        let selected_task_uuid = self.task_manager.borrow().tasks[selected_task_idx].uuid;
+        let blocker_uuids: Vec<Uuid> = if self.marked_task_uuids.is_empty() {
+            Vec::new()
+        } else {
+            self.target_task_uuids()
+                .into_iter()
+                .filter(|task_uuid| *task_uuid != selected_task_uuid)
+                .collect()
+        };
+
+        let block_result = self
+            .task_manager
+            .borrow_mut()
+            .set_task_blockers(selected_task_uuid, &blocker_uuids);
+        match block_result {
+            Ok(()) => {
+                self.marked_task_uuids.clear();
+                self.range_anchor_uuid = None;
+                None
+            }
+            Err(error) => Some(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error.to_string()),
+            }),
+        }
+    }
+
+    /// Focuses on the task under the cursor, refusing while tasks blocking it are not done
+    fn toggle_active_task(&mut self) -> Option<Action> {
+        let selected_task_idx = self.selected_task_idx()?;
+        let mut task_manager = self.task_manager.borrow_mut();
+        let selected_task = &task_manager.tasks[selected_task_idx];
+        let selected_task_uuid = selected_task.uuid;
+        let is_active =
+            task_manager.active_task().map(|task| task.uuid) == Some(selected_task_uuid);
+        let open_blocker_titles: Vec<String> = task_manager
+            .open_blockers(selected_task)
+            .iter()
+            .map(|blocker| blocker.title.clone())
+            .collect();
+
+        if !is_active && !open_blocker_titles.is_empty() {
+            return Some(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(format!(
+                    "'{}' is blocked by {}, which must be done first",
+                    selected_task.title,
+                    open_blocker_titles
+                        .iter()
+                        .map(|title| format!("'{}'", title))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )),
+            });
+        }
+        task_manager.toggle_active_task(selected_task_uuid);
+        None
+    }
+
+    /// Undoes or redoes the last change, keeping the cursor around the same row
+    fn travel_history(&mut self, redo: bool) -> Option<Action> {
+        let selected_row = self.list_state.selected().unwrap_or(0);
+        let history_result = if redo {
+            self.task_manager.borrow_mut().redo()
+        } else {
+            self.task_manager.borrow_mut().undo()
+        };
+
+        match history_result {
+            Ok(_) => {
+                self.select_row_near(selected_row);
+                None
+            }
+            Err(error) => Some(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error.to_string()),
+            }),
+        }
+    }
+
+    /// Types the search query, the filter applying as it is typed
+    fn handle_search_key_event(&mut self, key_event: &KeyEvent) {
+        match key_event.code {
+            KeyCode::Esc => {
+                self.search_query.clear();
+                self.is_searching = false;
+            }
+            KeyCode::Enter => self.is_searching = false,
+            KeyCode::Backspace => {
+                self.search_query.pop();
+            }
+            KeyCode::Char(c) => self.search_query.push(c),
+            _ => return,
+        }
+        self.list_state.select(Some(0));
     }
 
     fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
+        if self.is_searching {
+            self.handle_search_key_event(key_event);
+            return None;
+        }
+
         match key_event.code {
+            KeyCode::Char('/') => self.is_searching = true,
+            KeyCode::Char('?') => {
+                return Some(Action::OpenPopup {
+                    popup: self
+                        .popup_factory
+                        .create_task_list_help_popup(self.selected_task_idx().unwrap_or(0)),
+                });
+            }
+            KeyCode::Esc if !self.search_query.is_empty() => {
+                self.search_query.clear();
+                self.list_state.select(Some(0));
+            }
+            KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                return self.travel_history(true);
+            }
+            KeyCode::Char('u') => return self.travel_history(false),
+            KeyCode::Enter => {
+                if let Some(selected_task_idx) = self.selected_task_idx() {
+                    let selected_task_uuid =
+                        self.task_manager.borrow().tasks[selected_task_idx].uuid;
+                    return Some(Action::OpenPopup {
+                        popup: self
+                            .popup_factory
+                            .create_task_detail_popup(selected_task_uuid, selected_task_idx),
+                    });
+                }
+            }
+            KeyCode::Char('e') => {
+                if let Some(selected_task_idx) = self.selected_task_idx() {
+                    return Some(Action::OpenPopup {
+                        popup: self.popup_factory.create_edit_task_popup(selected_task_idx),
+                    });
+                }
+            }
+            KeyCode::Up if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                return self.move_selected_task(false);
+            }
+            KeyCode::Down if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                return self.move_selected_task(true);
+            }
+            KeyCode::Esc
+                if !self.marked_task_uuids.is_empty() || self.range_anchor_uuid.is_some() =>
+            {
+                self.marked_task_uuids.clear();
+                self.range_anchor_uuid = None;
+            }
             KeyCode::Char('t') | KeyCode::Esc => return Some(Action::ClosePopup),
+            KeyCode::Char(' ') => self.toggle_mark(),
+            KeyCode::Char('v') => self.mark_range(),
+            KeyCode::Char('f') => return self.toggle_active_task(),
+            KeyCode::Char('b') => return self.block_selected_task(),
+            KeyCode::Char('R') => {
+                let target_task_uuids = self.target_task_uuids();
+                if !target_task_uuids.is_empty() {
+                    return Some(Action::OpenPopup {
+                        popup: self.popup_factory.create_recurrence_popup(
+                            target_task_uuids,
+                            self.selected_task_idx().unwrap_or(0),
+                        ),
+                    });
+                }
+            }
+            KeyCode::Tab => return self.cycle_list(true),
+            KeyCode::BackTab => return self.cycle_list(false),
+            KeyCode::Char('L') => {
+                return Some(Action::OpenPopup {
+                    popup: self
+                        .popup_factory
+                        .create_select_list_popup(self.selected_task_idx().unwrap_or(0)),
+                });
+            }
+            KeyCode::Char('m') => {
+                let target_task_uuids = self.target_task_uuids();
+                if !target_task_uuids.is_empty() {
+                    return Some(Action::OpenPopup {
+                        popup: self.popup_factory.create_move_to_list_popup(
+                            target_task_uuids,
+                            self.selected_task_idx().unwrap_or(0),
+                        ),
+                    });
+                }
+            }
+            KeyCode::Char('E') => {
+                return Some(Action::OpenPopup {
+                    popup: self
+                        .popup_factory
+                        .create_export_csv_popup(self.selected_task_idx().unwrap_or(0)),
+                });
+            }
+            KeyCode::Right => return self.change_status(Some(true)),
+            KeyCode::Left => return self.change_status(Some(false)),
+            KeyCode::Char('c') => return self.change_status(None),
+            KeyCode::Char('S') => {
+                let target_task_uuids = self.target_task_uuids();
+                if !target_task_uuids.is_empty() {
+                    return Some(Action::OpenPopup {
+                        popup: self.popup_factory.create_set_status_popup(
+                            target_task_uuids,
+                            self.selected_task_idx().unwrap_or(0),
+                        ),
+                    });
+                }
+            }
+            KeyCode::Char('#') => {
+                let target_task_uuids = self.target_task_uuids();
+                if !target_task_uuids.is_empty() {
+                    return Some(Action::OpenPopup {
+                        popup: self.popup_factory.create_tag_tasks_popup(
+                            target_task_uuids,
+                            self.selected_task_idx().unwrap_or(0),
+                        ),
+                    });
+                }
+            }
             KeyCode::Char('a') => {
                 // TODO: Double check it it's correct to use .unwrap_or(0)
                 return Some(Action::OpenPopup {
                     popup: self
                         .popup_factory
-                        .create_add_task_popup(self.list_state.selected().unwrap_or(0)),
+                        .create_add_task_popup(self.selected_task_idx().unwrap_or(0)),
                 });
             }
             KeyCode::Char('d') => {
-                if let Some(selected_index) = &self.list_state.selected() {
-                    let task_manager_borrowed = self.task_manager.borrow();
-                    let selected_task_uuid = task_manager_borrowed.tasks[*selected_index].uuid;
-                    drop(task_manager_borrowed);
-
+                if let Some(selected_row) = self.list_state.selected() {
+                    let target_task_uuids = self.target_task_uuids();
                     let delete_task_result: Result<(), SaveTaskError> = self
                         .task_manager
                         .borrow_mut()
-                        .delete_task(selected_task_uuid);
-
-                    match delete_task_result {
-                        Ok(_) => {
-                            let new_len = self.task_manager.borrow().tasks.len();
-                            if new_len == 0 {
-                                self.list_state.select(None);
-                            } else if *selected_index >= new_len {
-                                self.list_state.select(Some(new_len - 1));
-                            }
-                            return None;
-                        }
-                        Err(error) => {
-                            return Some(Action::OpenPopup {
-                                popup: self.popup_factory.create_error_popup(error.to_string()),
-                            });
-                        }
-                    }
+                        .delete_tasks(&target_task_uuids);
+                    return self.after_removal(delete_task_result, selected_row);
                 }
             }
-            KeyCode::Up => {
-                if let Some(selected_index) = &self.list_state.selected() {
-                    let new_selected_index: usize = selected_index.saturating_sub(1);
-                    self.list_state.select(Some(new_selected_index));
+            KeyCode::Char('x') => {
+                if let Some(selected_row) = self.list_state.selected() {
+                    let target_task_uuids = self.target_task_uuids();
+                    let archive_result = self
+                        .task_manager
+                        .borrow_mut()
+                        .archive_tasks(&target_task_uuids);
+                    return self.after_removal(archive_result, selected_row);
                 }
             }
-            KeyCode::Down => {
-                if let Some(selected_index) = &self.list_state.selected() {
-                    let new_selected_index = usize::min(
-                        selected_index.saturating_add(1),
-                        self.task_manager.borrow().tasks.len() - 1,
-                    );
-                    self.list_state.select(Some(new_selected_index));
-                }
+            KeyCode::Char('X') => {
+                let selected_row = self.list_state.selected().unwrap_or(0);
+                let archive_result = self.task_manager.borrow_mut().archive_done_tasks();
+                return self.after_removal(archive_result, selected_row);
+            }
+            KeyCode::Char('A') => {
+                return Some(Action::OpenPopup {
+                    popup: self.popup_factory.create_archive_popup(),
+                });
+            }
+            KeyCode::Char('s') => {
+                return self.update_view(|view| view.sort_mode = view.sort_mode.next());
+            }
+            KeyCode::Char('g') => {
+                return self.update_view(|view| view.group_by_status = !view.group_by_status);
             }
+            KeyCode::Char('h') => {
+                return self.update_view(|view| view.done_visibility = view.done_visibility.next());
+            }
+            KeyCode::Up => self.move_selection(false),
+            KeyCode::Down => self.move_selection(true),
             _ => {}
         }
         None
@@ -174,25 +1037,106 @@ impl Popup for TaskListPopup {
         let inner_area: Rect = block.inner(popup_area);
         let inner_layout = Layout::default()
             .direction(ratatui::layout::Direction::Vertical)
-            .constraints(vec![Constraint::Length(1), Constraint::Fill(1)])
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+                Constraint::Length(2),
+            ])
             .split(inner_area);
 
-        let title_text: Text = Text::styled("Task list popup", theme.text_style()).centered();
-        let title_paragraphe: Paragraph = Paragraph::new(title_text).centered();
+        let task_manager = self.task_manager.borrow();
+        let list_tabs: Tabs = Tabs::new(
+            task_manager
+                .list_names()
+                .iter()
+                .map(|list| list_display_name(list).to_string()),
+        )
+        .select(
+            task_manager
+                .list_names()
+                .iter()
+                .position(|list| *list == task_manager.selected_list),
+        )
+        .style(theme.text_style())
+        .highlight_style(theme.text_style().bold().underlined());
+        let title_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints(vec![Constraint::Fill(1), Constraint::Fill(1)])
+            .split(inner_layout[0]);
+        let stats_paragraph: Paragraph =
+            Paragraph::new(Text::styled(self.list_stats(), theme.text_style())).right_aligned();
 
+        let active_task_uuid = task_manager.active_task().map(|task| task.uuid);
         let task_list: List = List::new(
-            self.task_manager
-                .borrow()
-                .tasks
+            self.view()
+                .rows(
+                    &task_manager.tasks,
+                    &task_manager.selected_list,
+                    &self.search_query,
+                )
                 .iter()
-                .map(|task| task.get_list_item().style(theme.text_style()))
+                .map(|row| match row {
+                    TaskListRow::Header(status) => {
+                        ListItem::new(Line::from(format!("── {} ──", status)).centered())
+                            .style(theme.text_style().add_modifier(Modifier::DIM))
+                    }
+                    TaskListRow::Task(task_idx) => {
+                        let task = &task_manager.tasks[*task_idx];
+                        let is_marked = self.marked_task_uuids.contains(&task.uuid)
+                            || self.range_anchor_uuid == Some(task.uuid);
+                        let mut style = if is_marked {
+                            theme.marked_text_style()
+                        } else {
+                            theme.text_style()
+                        };
+                        if active_task_uuid == Some(task.uuid) {
+                            style = style.add_modifier(Modifier::UNDERLINED);
+                        }
+                        task.get_list_item(!task_manager.open_blockers(task).is_empty())
+                            .style(style)
+                    }
+                })
                 .collect::<Vec<ListItem>>(),
         )
         .highlight_style(Style::default().fg(theme.text_color).bold());
+        drop(task_manager);
+
+        let view = self.view();
+        let mut view_content = format!(
+            "Sort (s): {} | Group by status (g): {} | Done tasks (h): {}",
+            view.sort_mode,
+            if view.group_by_status { "on" } else { "off" },
+            view.done_visibility
+        );
+        if self.is_searching {
+            view_content.push_str(&format!(" | Search: {}▏", self.search_query));
+        } else if !self.search_query.is_empty() {
+            view_content.push_str(&format!(" | Search: {}, Esc to clear", self.search_query));
+        }
+        if self.range_anchor_uuid.is_some() {
+            view_content.push_str(" | Range started, V to end it");
+        } else if !self.marked_task_uuids.is_empty() {
+            view_content.push_str(&format!(" | {} marked", self.marked_task_uuids.len()));
+        }
+        let view_text: Text = Text::styled(view_content, theme.text_style()).centered();
+        let view_paragraph: Paragraph = Paragraph::new(view_text).centered();
+
+        let controls_text: Text = Text::styled(
+            "Controls: Enter for details, A to add, E to edit, D to delete, C to complete, Space to mark, / to search, ? for all keys",
+            theme.text_style(),
+        )
+        .centered();
+        let controls_paragraph: Paragraph = Paragraph::new(controls_text)
+            .wrap(Wrap { trim: true })
+            .centered();
 
         frame.render_widget(block, popup_area);
-        frame.render_widget(title_paragraphe, inner_layout[0]);
+        frame.render_widget(list_tabs, title_layout[0]);
+        frame.render_widget(stats_paragraph, title_layout[1]);
         frame.render_stateful_widget(task_list, inner_layout[1], &mut self.list_state);
+        frame.render_widget(view_paragraph, inner_layout[2]);
+        frame.render_widget(controls_paragraph, inner_layout[3]);
     }
 
     fn handle_event(&mut self, event: &Event) -> Option<Action> {
@@ -202,4 +1146,8 @@ impl Popup for TaskListPopup {
             _ => None,
         }
     }
+
+    fn is_typing(&self) -> bool {
+        self.is_searching
+    }
 }
@@ -0,0 +1,89 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+/// Fields of a task typed in one line, such as `Write report #docs !p1 ~3 due:fri`
+///
+/// `#docs` adds a tag, `!p1` to `!p26` or `!a` to `!z` set the priority from `A` to `Z`, `~3`
+/// estimates the task at 3 focus sessions and `due:` takes `today`, `tomorrow`, a day of the
+/// week or a `YYYY-MM-DD` date. Other words make up the title.
+#[derive(Default)]
+pub struct QuickAdd {
+    pub title: String,
+    pub tags: Vec<String>,
+    pub priority: Option<char>,
+    pub estimate: Option<u32>,
+    pub due_date: Option<NaiveDate>,
+}
+
+impl QuickAdd {
+    pub fn parse(text: &str, today: NaiveDate) -> Self {
+        let mut quick_add = QuickAdd::default();
+        let mut title_words: Vec<&str> = Vec::new();
+
+        for word in text.split_whitespace() {
+            if let Some(tag) = word.strip_prefix('#').filter(|tag| !tag.is_empty()) {
+                if !quick_add.tags.iter().any(|task_tag| task_tag == tag) {
+                    quick_add.tags.push(tag.to_string());
+                }
+            } else if let Some(priority) = word.strip_prefix('!').and_then(parse_priority) {
+                quick_add.priority = Some(priority);
+            } else if let Some(estimate) = word.strip_prefix('~').and_then(parse_estimate) {
+                quick_add.estimate = Some(estimate);
+            } else if let Some(due_date) = word
+                .strip_prefix("due:")
+                .and_then(|due| parse_due_date(due, today))
+            {
+                quick_add.due_date = Some(due_date);
+            } else {
+                title_words.push(word);
+            }
+        }
+        quick_add.title = title_words.join(" ");
+        quick_add
+    }
+
+    /// Whether any field besides the title was typed
+    pub fn has_fields(&self) -> bool {
+        !self.tags.is_empty()
+            || self.priority.is_some()
+            || self.estimate.is_some()
+            || self.due_date.is_some()
+    }
+}
+
+/// Reads `p1` as `A` up to `p26` as `Z`, or a single letter
+fn parse_priority(text: &str) -> Option<char> {
+    let priority_number = text
+        .strip_prefix(['p', 'P'])
+        .filter(|priority_number| !priority_number.is_empty());
+    let priority_idx: u8 = match priority_number {
+        Some(priority_number) => priority_number.parse::<u8>().ok()?.checked_sub(1)?,
+        None => {
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(letter), None) if letter.is_ascii_alphabetic() => {
+                    letter.to_ascii_uppercase() as u8 - b'A'
+                }
+                _ => return None,
+            }
+        }
+    };
+    (priority_idx < 26).then(|| (b'A' + priority_idx) as char)
+}
+
+fn parse_estimate(text: &str) -> Option<u32> {
+    text.parse::<u32>().ok().filter(|estimate| *estimate > 0)
+}
+
+/// A day of the week being the next one to come, a week away when it is today
+fn parse_due_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match text.to_lowercase().as_str() {
+        "today" => Some(today),
+        "tomorrow" => today.checked_add_days(Days::new(1)),
+        text => match text.parse::<Weekday>() {
+            Ok(weekday) => (1..=7)
+                .map(|day_count| today + Days::new(day_count))
+                .find(|date| date.weekday() == weekday),
+            Err(_) => NaiveDate::parse_from_str(text, "%Y-%m-%d").ok(),
+        },
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt::Display;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// When a recurring task is due again once done
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub enum Recurrence {
+    Daily,
+    /// Monday to Friday
+    Weekdays,
+    Weekly(Vec<Weekday>),
+    /// Counted from the last completion
+    EveryNDays(u32),
+}
+
+impl Recurrence {
+    /// Reads a rule such as `daily`, `weekdays`, `weekly mon thu` or `every 3 days`, as well as
+    /// the short forms written by `code`
+    pub fn parse(text: &str) -> Option<Recurrence> {
+        let text = text.trim().to_lowercase();
+        let words: Vec<&str> = text
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        match words.as_slice() {
+            ["daily"] | ["every", "day"] => Some(Recurrence::Daily),
+            ["weekdays"] | ["every", "weekday"] => Some(Recurrence::Weekdays),
+            ["every", day_count, "days"] => parse_day_count(day_count),
+            // `3d`, not a day name ending in `d` such as `wed`
+            [day_count]
+                if day_count
+                    .strip_suffix('d')
+                    .is_some_and(|day_count| day_count.parse::<u32>().is_ok()) =>
+            {
+                parse_day_count(day_count.trim_end_matches('d'))
+            }
+            _ => {
+                let day_names = match words.as_slice() {
+                    ["weekly", "on", day_names @ ..] | ["weekly", day_names @ ..] => day_names,
+                    day_names => day_names,
+                };
+                let mut days: Vec<Weekday> = day_names
+                    .iter()
+                    .map(|day_name| day_name.parse::<Weekday>().ok())
+                    .collect::<Option<Vec<Weekday>>>()?;
+                days.sort_by_key(Weekday::num_days_from_monday);
+                days.dedup();
+                (!days.is_empty()).then_some(Recurrence::Weekly(days))
+            }
+        }
+    }
+
+    /// Short form without spaces, as written in todo.txt files
+    pub fn code(&self) -> String {
+        match self {
+            Recurrence::Daily => String::from("daily"),
+            Recurrence::Weekdays => String::from("weekdays"),
+            Recurrence::Weekly(days) => days
+                .iter()
+                .map(|day| day.to_string().to_lowercase())
+                .collect::<Vec<String>>()
+                .join(","),
+            Recurrence::EveryNDays(day_count) => format!("{}d", day_count),
+        }
+    }
+
+    fn is_due_on(&self, date: NaiveDate) -> bool {
+        match self {
+            Recurrence::Daily | Recurrence::EveryNDays(_) => true,
+            Recurrence::Weekdays => date.weekday().num_days_from_monday() < 5,
+            Recurrence::Weekly(days) => days.contains(&date.weekday()),
+        }
+    }
+
+    /// First day the task is due again after being done on `date`
+    pub fn next_due_date(&self, date: NaiveDate) -> NaiveDate {
+        let day_count = match self {
+            Recurrence::EveryNDays(day_count) => u64::from(*day_count).max(1),
+            _ => (1..=7)
+                .find(|day_count| self.is_due_on(date + Days::new(*day_count)))
+                .unwrap_or(1),
+        };
+        date + Days::new(day_count)
+    }
+
+    /// Number of completions in a row without a due day missed in between, today not counting
+    /// as missed until it is over
+    pub fn streak(&self, completion_dates: &[NaiveDate], today: NaiveDate) -> usize {
+        let mut completion_dates: Vec<NaiveDate> = completion_dates.to_vec();
+        completion_dates.sort_unstable_by(|first, second| second.cmp(first));
+        completion_dates.dedup();
+
+        let Some(last_completion_date) = completion_dates.first() else {
+            return 0;
+        };
+        if self.next_due_date(*last_completion_date) < today {
+            return 0;
+        }
+        1 + completion_dates
+            .windows(2)
+            .take_while(|dates| self.next_due_date(dates[1]) >= dates[0])
+            .count()
+    }
+}
+
+fn parse_day_count(day_count: &str) -> Option<Recurrence> {
+    match day_count.parse::<u32>().ok()? {
+        0 => None,
+        1 => Some(Recurrence::Daily),
+        day_count => Some(Recurrence::EveryNDays(day_count)),
+    }
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekdays => write!(f, "weekdays"),
+            Recurrence::Weekly(days) => write!(
+                f,
+                "weekly on {}",
+                days.iter()
+                    .map(Weekday::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Recurrence::EveryNDays(day_count) => write!(f, "every {} days", day_count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_are_read_back_from_their_code() {
+        let rules = [
+            Recurrence::Daily,
+            Recurrence::Weekdays,
+            Recurrence::Weekly(vec![Weekday::Wed]),
+            Recurrence::Weekly(vec![Weekday::Mon, Weekday::Thu]),
+            Recurrence::EveryNDays(3),
+        ];
+        for rule in rules {
+            assert!(Recurrence::parse(&rule.code()) == Some(rule));
+        }
+    }
+
+    #[test]
+    fn day_counts_and_day_names_are_told_apart() {
+        assert!(Recurrence::parse("3d") == Some(Recurrence::EveryNDays(3)));
+        assert!(Recurrence::parse("wed") == Some(Recurrence::Weekly(vec![Weekday::Wed])));
+        assert!(Recurrence::parse("weekly wed") == Some(Recurrence::Weekly(vec![Weekday::Wed])));
+        assert!(Recurrence::parse("0d").is_none());
+        assert!(Recurrence::parse("d").is_none());
+    }
+}
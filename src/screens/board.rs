@@ -0,0 +1,286 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+};
+use uuid::Uuid;
+
+use crate::{
+    app::{Action, Screen},
+    popup_factory::PopupFactory,
+    popups::task_list::{TaskStatus, list_display_name},
+    storage::TaskManager,
+    theme::Theme,
+    workflow::workflow,
+};
+
+/// Tasks of one status, as indices in `TaskManager::tasks`
+struct BoardColumn {
+    status: TaskStatus,
+    task_indices: Vec<usize>,
+}
+
+/// Full-screen board of the selected list, with one column per status of the workflow
+pub struct BoardScreen {
+    popup_factory: Rc<PopupFactory>,
+    task_manager: Rc<RefCell<TaskManager>>,
+    selected_column: usize,
+    selected_row: usize,
+}
+
+impl BoardScreen {
+    pub fn new(popup_factory: Rc<PopupFactory>, task_manager: Rc<RefCell<TaskManager>>) -> Self {
+        BoardScreen {
+            popup_factory,
+            task_manager,
+            selected_column: 0,
+            selected_row: 0,
+        }
+    }
+
+    /// The statuses of the workflow in order, then those of tasks the workflow no longer has
+    fn columns(task_manager: &TaskManager) -> Vec<BoardColumn> {
+        let mut columns: Vec<BoardColumn> = workflow()
+            .statuses
+            .iter()
+            .map(|status| BoardColumn {
+                status: TaskStatus::new(&status.name),
+                task_indices: Vec::new(),
+            })
+            .collect();
+
+        for (task_idx, task) in task_manager.tasks.iter().enumerate() {
+            if task.list != task_manager.selected_list {
+                continue;
+            }
+            match columns
+                .iter_mut()
+                .find(|column| column.status == task.status)
+            {
+                Some(column) => column.task_indices.push(task_idx),
+                None => columns.push(BoardColumn {
+                    status: task.status.clone(),
+                    task_indices: vec![task_idx],
+                }),
+            }
+        }
+        columns
+    }
+
+    /// Column and row of the selection, kept within the tasks shown
+    fn selection(&self, columns: &[BoardColumn]) -> (usize, usize) {
+        let selected_column = self.selected_column.min(columns.len().saturating_sub(1));
+        let row_count = columns
+            .get(selected_column)
+            .map(|column| column.task_indices.len())
+            .unwrap_or(0);
+        (
+            selected_column,
+            self.selected_row.min(row_count.saturating_sub(1)),
+        )
+    }
+
+    fn selected_task_uuid(&self) -> Option<Uuid> {
+        let task_manager = self.task_manager.borrow();
+        let columns = Self::columns(&task_manager);
+        let (selected_column, selected_row) = self.selection(&columns);
+        let task_idx = columns
+            .get(selected_column)?
+            .task_indices
+            .get(selected_row)?;
+        Some(task_manager.tasks[*task_idx].uuid)
+    }
+
+    fn move_selection(&mut self, column_offset: isize, row_offset: isize) {
+        let task_manager = self.task_manager.borrow();
+        let columns = Self::columns(&task_manager);
+        let (selected_column, selected_row) = self.selection(&columns);
+        self.selected_column = selected_column
+            .saturating_add_signed(column_offset)
+            .min(columns.len().saturating_sub(1));
+        self.selected_row = selected_row.saturating_add_signed(row_offset);
+    }
+
+    /// Moves the selected task to the column next to its own, the selection following it
+    ///
+    /// Columns of statuses the workflow no longer has only let their tasks out.
+    fn move_selected_task(&mut self, forward: bool) -> Option<Action> {
+        let task_uuid = self.selected_task_uuid()?;
+        let columns = Self::columns(&self.task_manager.borrow());
+        let (selected_column, _) = self.selection(&columns);
+        let new_column = if forward {
+            selected_column + 1
+        } else {
+            selected_column.checked_sub(1)?
+        };
+        let new_status = columns.get(new_column)?.status.clone();
+        if workflow().status(new_status.name()).is_none() {
+            return Some(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(format!(
+                    "Tasks cannot be moved to '{}', which is not a status of the workflow",
+                    new_status
+                )),
+            });
+        }
+
+        let status_result = self
+            .task_manager
+            .borrow_mut()
+            .set_tasks_status(&[task_uuid], new_status);
+        match status_result {
+            Ok(completed_task_uuids) => {
+                // Columns of statuses no longer in the workflow may be gone once rebuilt
+                self.select_task(task_uuid);
+                Some(Action::TasksCompleted {
+                    task_uuids: completed_task_uuids,
+                })
+            }
+            Err(error) => Some(Action::OpenPopup {
+                popup: self.popup_factory.create_error_popup(error.to_string()),
+            }),
+        }
+    }
+
+    /// Selects the task in whichever column it now is, keeping the selection when it is gone
+    fn select_task(&mut self, task_uuid: Uuid) {
+        let task_manager = self.task_manager.borrow();
+        let columns = Self::columns(&task_manager);
+        let task_position = columns.iter().enumerate().find_map(|(column_idx, column)| {
+            column
+                .task_indices
+                .iter()
+                .position(|task_idx| task_manager.tasks[*task_idx].uuid == task_uuid)
+                .map(|row_idx| (column_idx, row_idx))
+        });
+        if let Some((column_idx, row_idx)) = task_position {
+            self.selected_column = column_idx;
+            self.selected_row = row_idx;
+        }
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<Action> {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('b') => return Some(Action::CloseBoard),
+            KeyCode::Left if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                return self.move_selected_task(false);
+            }
+            KeyCode::Right if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                return self.move_selected_task(true);
+            }
+            KeyCode::Left => self.move_selection(-1, 0),
+            KeyCode::Right => self.move_selection(1, 0),
+            KeyCode::Up => self.move_selection(0, -1),
+            KeyCode::Down => self.move_selection(0, 1),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl Screen for BoardScreen {
+    fn draw(&self, frame: &mut ratatui::Frame, area: Rect, theme: &Theme) {
+        let vertical_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        let task_manager = self.task_manager.borrow();
+        let title_text: Text = Text::styled(
+            format!(
+                "Board of {}",
+                list_display_name(&task_manager.selected_list)
+            ),
+            theme.text_style().add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let title_paragraph: Paragraph = Paragraph::new(title_text).centered();
+
+        let columns = Self::columns(&task_manager);
+        let (selected_column, selected_row) = self.selection(&columns);
+        let column_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(columns.iter().map(|_| Constraint::Fill(1)))
+            .split(vertical_layout[1]);
+
+        for (column_idx, column) in columns.iter().enumerate() {
+            let is_selected = column_idx == selected_column;
+            let mut title_style = theme.text_style();
+            if let Some(status_color) = column.status.color() {
+                title_style = title_style.fg(status_color);
+            }
+            let column_block = Block::bordered()
+                .border_style(if is_selected {
+                    theme.marked_text_style()
+                } else {
+                    theme.border_style
+                })
+                .title(
+                    Line::styled(
+                        format!(
+                            " {} {} ({}) ",
+                            column.status.emoji(),
+                            column.status,
+                            column.task_indices.len()
+                        ),
+                        title_style.add_modifier(Modifier::BOLD),
+                    )
+                    .centered(),
+                );
+
+            let active_task_uuid = task_manager.active_task().map(|task| task.uuid);
+            let column_list: List = List::new(
+                column
+                    .task_indices
+                    .iter()
+                    .map(|task_idx| {
+                        let task = &task_manager.tasks[*task_idx];
+                        let mut style = theme.text_style();
+                        if active_task_uuid == Some(task.uuid) {
+                            style = style.add_modifier(Modifier::UNDERLINED);
+                        }
+                        task.get_list_item(!task_manager.open_blockers(task).is_empty())
+                            .style(style)
+                    })
+                    .collect::<Vec<ListItem>>(),
+            )
+            .block(column_block)
+            .highlight_style(Style::default().fg(theme.text_color).bold().reversed());
+
+            // Only the selected column shows a cursor
+            let mut list_state =
+                ListState::default().with_selected(is_selected.then_some(selected_row));
+            frame.render_stateful_widget(column_list, column_areas[column_idx], &mut list_state);
+        }
+        drop(task_manager);
+
+        let controls_text: Text = Text::styled(
+            "Controls: Arrows to select a task, Shift+Left/Right to move it to another status, T for the task list, Esc/B to leave the board",
+            theme.text_style(),
+        )
+        .centered();
+        let controls_paragraph: Paragraph = Paragraph::new(controls_text).centered();
+
+        frame.render_widget(title_paragraph, vertical_layout[0]);
+        frame.render_widget(controls_paragraph, vertical_layout[2]);
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event),
+
+            _ => None,
+        }
+    }
+
+    fn update(&mut self) -> Option<Action> {
+        None
+    }
+}
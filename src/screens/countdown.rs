@@ -1,5 +1,7 @@
 use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
+
 use crate::app::{Action, Screen};
 use crate::theme::Theme;
 use crate::utils::{CountdownType, popup_area};
@@ -16,6 +18,8 @@ use tui_big_text::{BigText, PixelSize};
 
 pub struct CountdownScreen {
     start_time: Instant,
+    /// Wall-clock start of the current phase, pauses included, for the session history
+    phase_started_at: DateTime<Utc>,
     total_duration: Duration,
     is_paused: bool,
     remaining_time_when_paused: Option<Duration>,
@@ -28,6 +32,7 @@ impl CountdownScreen {
     pub fn new(work_duration_minutes: u64, break_duration_minutes: u64) -> Self {
         CountdownScreen {
             start_time: Instant::now(),
+            phase_started_at: Utc::now(),
             total_duration: Duration::from_mins(work_duration_minutes),
             is_paused: false,
             remaining_time_when_paused: None,
@@ -49,24 +54,21 @@ impl CountdownScreen {
     }
 
     fn handle_key_event(&mut self, key_event: &KeyEvent) {
-        match key_event.code {
-            KeyCode::Char(' ') => {
-                if self.is_paused {
-                    if let Some(remaining_time) = self.remaining_time_when_paused {
-                        let elapsed_during_pause = self.total_duration - remaining_time;
-                        self.start_time = Instant::now() - elapsed_during_pause;
-                    }
-                    self.remaining_time_when_paused = None;
-                    self.is_paused = false;
-                } else {
-                    self.remaining_time_when_paused = Some(
-                        self.total_duration
-                            .saturating_sub(self.start_time.elapsed()),
-                    );
-                    self.is_paused = true;
+        if key_event.code == KeyCode::Char(' ') {
+            if self.is_paused {
+                if let Some(remaining_time) = self.remaining_time_when_paused {
+                    let elapsed_during_pause = self.total_duration - remaining_time;
+                    self.start_time = Instant::now() - elapsed_during_pause;
                 }
+                self.remaining_time_when_paused = None;
+                self.is_paused = false;
+            } else {
+                self.remaining_time_when_paused = Some(
+                    self.total_duration
+                        .saturating_sub(self.start_time.elapsed()),
+                );
+                self.is_paused = true;
             }
-            _ => {}
         }
     }
 
@@ -161,8 +163,15 @@ impl Screen for CountdownScreen {
         }
     }
 
-    fn update(&mut self) {
+    fn update(&mut self) -> Option<Action> {
         if !self.is_paused && self.remaining_duration().as_secs() == 0 {
+            let finished_session = Action::RecordSession {
+                start: self.phase_started_at,
+                end: Utc::now(),
+                phase: self.countdown_type,
+                duration_seconds: self.total_duration.as_secs(),
+            };
+
             self.countdown_type = match self.countdown_type {
                 CountdownType::Work => CountdownType::Break,
                 CountdownType::Break => CountdownType::Work,
@@ -189,7 +198,10 @@ impl Screen for CountdownScreen {
             }
 
             self.start_time = Instant::now();
+            self.phase_started_at = Utc::now();
             self.remaining_time_when_paused = None;
+            return Some(finished_session);
         }
+        None
     }
 }
@@ -1,2 +1,4 @@
+pub mod board;
 pub mod countdown;
+pub mod recovery;
 pub mod welcome;
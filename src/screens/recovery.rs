@@ -0,0 +1,221 @@
+use std::{fs::read_to_string, io};
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::{
+    storage::{LoadTaskFileError, file::TaskBackup},
+    theme::Theme,
+};
+
+/// What to do about tasks that cannot be loaded
+pub enum RecoveryChoice {
+    RestoreBackup(TaskBackup),
+    ReadOnly,
+    MoveAside,
+    Quit,
+}
+
+/// Shown instead of the app when the tasks cannot be loaded, until the user picks a way out
+pub struct RecoveryScreen {
+    error_message: String,
+    /// Line and column of a parsing error, with the text of that line
+    error_location: Option<(usize, usize, String)>,
+    /// Readable backups with their number of tasks
+    backups: Vec<(TaskBackup, usize)>,
+    /// Whether the files may be changed, which another instance using them forbids
+    writable: bool,
+    list_state: ListState,
+}
+
+impl RecoveryScreen {
+    pub fn new(
+        error: &LoadTaskFileError,
+        backups: Vec<(TaskBackup, usize)>,
+        writable: bool,
+    ) -> Self {
+        let error_location = match error {
+            LoadTaskFileError::ParsingError(path, parsing_error) if parsing_error.line() > 0 => {
+                read_to_string(path).ok().and_then(|content| {
+                    let line = content.lines().nth(parsing_error.line() - 1)?;
+                    Some((
+                        parsing_error.line(),
+                        parsing_error.column(),
+                        line.to_string(),
+                    ))
+                })
+            }
+            _ => None,
+        };
+
+        RecoveryScreen {
+            error_message: error.to_string(),
+            error_location,
+            backups,
+            writable,
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    fn choices(&self) -> Vec<(String, RecoveryChoice)> {
+        let mut choices: Vec<(String, RecoveryChoice)> = self
+            .backups
+            .iter()
+            .map(|(backup, task_count)| {
+                let modified_at = backup
+                    .modified_at
+                    .map(|modified_at| {
+                        DateTime::<Local>::from(modified_at)
+                            .format(", saved %Y-%m-%d %H:%M")
+                            .to_string()
+                    })
+                    .unwrap_or_default();
+                (
+                    format!(
+                        "Restore backup {} ({} task(s){})",
+                        backup.number, task_count, modified_at
+                    ),
+                    RecoveryChoice::RestoreBackup(backup.clone()),
+                )
+            })
+            .collect();
+        choices.push((
+            "Open read-only, picking up the file once it is fixed".to_string(),
+            RecoveryChoice::ReadOnly,
+        ));
+        if self.writable {
+            choices.push((
+                "Move the file aside and start with no tasks".to_string(),
+                RecoveryChoice::MoveAside,
+            ));
+        }
+        choices.push(("Quit".to_string(), RecoveryChoice::Quit));
+        choices
+    }
+
+    /// Runs until a choice is made, with the terminal already set up for the TUI
+    pub fn run(
+        mut self,
+        terminal: &mut DefaultTerminal,
+        theme: &Theme,
+    ) -> io::Result<RecoveryChoice> {
+        loop {
+            terminal.draw(|frame| self.draw(frame, theme))?;
+            if let Some(choice) = self.handle_event(&event::read()?) {
+                return Ok(choice);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event) -> Option<RecoveryChoice> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        if key_event.kind != KeyEventKind::Press {
+            return None;
+        }
+
+        match key_event.code {
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Down => {
+                let last_idx = self.choices().len() - 1;
+                let selected_idx = self.list_state.selected().unwrap_or(0);
+                self.list_state
+                    .select(Some(usize::min(selected_idx + 1, last_idx)));
+            }
+            KeyCode::Enter => {
+                let selected_idx = self.list_state.selected().unwrap_or(0);
+                return self
+                    .choices()
+                    .into_iter()
+                    .nth(selected_idx)
+                    .map(|(_, choice)| choice);
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Some(RecoveryChoice::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, theme: &Theme) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border_style)
+            .style(theme.background_style);
+        let inner_area: Rect = block.inner(frame.area());
+        let inner_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Length(4),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+            ])
+            .split(inner_area);
+
+        let title_paragraph: Paragraph = Paragraph::new(
+            Text::styled("The tasks could not be loaded", theme.text_style()).centered(),
+        );
+        let error_paragraph: Paragraph =
+            Paragraph::new(Text::styled(&self.error_message, theme.error_text_style()))
+                .wrap(Wrap { trim: true });
+
+        // The text around the error, cut to a window so that the marker stays visible
+        let location_text: Text = match &self.error_location {
+            Some((line_number, column, line)) => {
+                let characters: Vec<char> = line.chars().collect();
+                let window_start = column.saturating_sub(60);
+                let window_end = usize::min(characters.len(), window_start + 120);
+                let window: String = characters[window_start.min(window_end)..window_end]
+                    .iter()
+                    .collect();
+                let marker = format!("{}^", " ".repeat(column.saturating_sub(1) - window_start));
+                Text::from(vec![
+                    Line::styled(
+                        format!("Line {}, column {}:", line_number, column),
+                        theme.text_style(),
+                    ),
+                    Line::styled(window, theme.text_style()),
+                    Line::styled(marker, theme.error_text_style()),
+                ])
+            }
+            None => Text::default(),
+        };
+
+        let choice_list: List = List::new(
+            self.choices()
+                .into_iter()
+                .map(|(label, _)| ListItem::new(label).style(theme.text_style()))
+                .collect::<Vec<ListItem>>(),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.text_color)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+        let controls_paragraph: Paragraph = Paragraph::new(
+            Text::styled(
+                "Controls: Up/Down to pick an option, Enter to confirm, Esc/Q to quit",
+                theme.text_style(),
+            )
+            .centered(),
+        );
+
+        frame.render_widget(block, frame.area());
+        frame.render_widget(title_paragraph, inner_layout[0]);
+        frame.render_widget(error_paragraph, inner_layout[1]);
+        frame.render_widget(Paragraph::new(location_text), inner_layout[2]);
+        frame.render_stateful_widget(choice_list, inner_layout[3], &mut self.list_state);
+        frame.render_widget(controls_paragraph, inner_layout[4]);
+    }
+}
@@ -145,5 +145,7 @@ impl Screen for WelcomeScreen {
         }
     }
 
-    fn update(&mut self) {}
+    fn update(&mut self) -> Option<Action> {
+        None
+    }
 }
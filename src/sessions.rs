@@ -0,0 +1,159 @@
+use std::{fmt::Display, fs::read_to_string, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{storage::file::write_atomically, utils::CountdownType};
+
+/// A countdown phase that ran to its end
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub phase: CountdownType,
+    /// Length of the phase, which excludes the time spent paused between `start` and `end`
+    pub duration_seconds: u64,
+    pub task_uuid: Option<Uuid>,
+    /// Kept so the session still names its task once the task is deleted
+    pub task_title: Option<String>,
+}
+
+/// Where `SessionManager` loads the session history from and appends to it
+pub trait SessionStore {
+    fn load(&mut self) -> Result<Vec<Session>, SessionFileError>;
+
+    /// Saves a finished session, `sessions` being the whole history including it
+    fn add_session(
+        &mut self,
+        session: &Session,
+        sessions: &[Session],
+    ) -> Result<(), SessionFileError>;
+}
+
+pub struct SessionManager {
+    pub sessions: Vec<Session>,
+    store: Box<dyn SessionStore>,
+}
+
+/// Keeps the whole history in a JSON file, rewritten on every new session
+pub struct FileSessionStore {
+    sessions_file_path: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum SessionFileError {
+    IoError(PathBuf, std::io::Error),
+    JsonError(PathBuf, serde_json::Error),
+    ReadOnly,
+    #[cfg(feature = "sqlite")]
+    Database(PathBuf, rusqlite::Error),
+}
+
+impl Display for SessionFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionFileError::IoError(path, error) => write!(
+                f,
+                "An I/O error occurred with the session history at {}: {}",
+                path.display(),
+                error
+            ),
+            SessionFileError::JsonError(path, error) => write!(
+                f,
+                "JSON error in the session history at {}: {}",
+                path.display(),
+                error
+            ),
+            SessionFileError::ReadOnly => write!(
+                f,
+                "The session history was opened read-only, so the session was not recorded"
+            ),
+            #[cfg(feature = "sqlite")]
+            SessionFileError::Database(path, error) => write!(
+                f,
+                "Database error in the session history at {}: {}",
+                path.display(),
+                error
+            ),
+        }
+    }
+}
+
+impl SessionManager {
+    pub fn with_store(mut store: Box<dyn SessionStore>) -> Result<Self, SessionFileError> {
+        Ok(SessionManager {
+            sessions: store.load()?,
+            store,
+        })
+    }
+
+    pub fn add_session(&mut self, session: Session) -> Result<(), SessionFileError> {
+        let mut new_sessions: Vec<Session> = self.sessions.clone();
+        new_sessions.push(session);
+
+        self.store
+            .add_session(new_sessions.last().unwrap(), &new_sessions)?;
+
+        self.sessions = new_sessions;
+        Ok(())
+    }
+}
+
+/// Loads the history of another store but refuses new sessions
+pub struct ReadOnlySessionStore {
+    store: Box<dyn SessionStore>,
+}
+
+impl ReadOnlySessionStore {
+    pub fn new(store: Box<dyn SessionStore>) -> Self {
+        ReadOnlySessionStore { store }
+    }
+}
+
+impl SessionStore for ReadOnlySessionStore {
+    fn load(&mut self) -> Result<Vec<Session>, SessionFileError> {
+        self.store.load()
+    }
+
+    fn add_session(
+        &mut self,
+        _session: &Session,
+        _sessions: &[Session],
+    ) -> Result<(), SessionFileError> {
+        Err(SessionFileError::ReadOnly)
+    }
+}
+
+impl FileSessionStore {
+    pub fn new(sessions_file_path: PathBuf) -> Self {
+        FileSessionStore { sessions_file_path }
+    }
+}
+
+impl SessionStore for FileSessionStore {
+    fn load(&mut self) -> Result<Vec<Session>, SessionFileError> {
+        match read_to_string(&self.sessions_file_path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|error| {
+                SessionFileError::JsonError(self.sessions_file_path.clone(), error)
+            }),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(error) => Err(SessionFileError::IoError(
+                self.sessions_file_path.clone(),
+                error,
+            )),
+        }
+    }
+
+    fn add_session(
+        &mut self,
+        _session: &Session,
+        sessions: &[Session],
+    ) -> Result<(), SessionFileError> {
+        let sessions_json_string: String = serde_json::to_string_pretty(sessions)
+            .map_err(|error| SessionFileError::JsonError(self.sessions_file_path.clone(), error))?;
+
+        write_atomically(&self.sessions_file_path, sessions_json_string.as_bytes())
+            .map_err(|error| SessionFileError::IoError(self.sessions_file_path.clone(), error))
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    fmt::Display,
+    fs::read_to_string,
+    io::{self, ErrorKind},
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{popups::task_list::TaskListView, storage::file::write_atomically, workflow::Workflow};
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub task_list_view: TaskListView,
+    /// List shown when the app starts, `None` being the default list
+    pub selected_task_list: Option<String>,
+    /// Only read when the app starts
+    pub workflow: Workflow,
+}
+
+pub struct SettingsManager {
+    pub settings: Settings,
+    settings_file_path: PathBuf,
+    /// Set when the settings file could not be loaded, so that it is not saved over
+    read_only: bool,
+}
+
+pub enum LoadSettingsError {
+    FileReadError(PathBuf, io::Error),
+    JsonParseError(PathBuf, serde_json::Error),
+}
+
+impl Display for LoadSettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadSettingsError::FileReadError(path, error) => write!(
+                f,
+                "Failed to read the settings file '{}': {}",
+                path.display(),
+                error
+            ),
+            LoadSettingsError::JsonParseError(path, error) => write!(
+                f,
+                "Failed to parse the settings file '{}': {}",
+                path.display(),
+                error
+            ),
+        }?;
+        write!(
+            f,
+            ". The default settings are used and the file is left as it is until the next start."
+        )
+    }
+}
+
+pub enum SaveSettingsError {
+    JsonConversionError(PathBuf, serde_json::Error),
+    FileWriteError(PathBuf, std::io::Error),
+}
+
+impl Display for SaveSettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveSettingsError::JsonConversionError(path, error) => write!(
+                f,
+                "Failed to serialize settings to JSON for file '{}': {}",
+                path.display(),
+                error
+            ),
+            SaveSettingsError::FileWriteError(path, error) => write!(
+                f,
+                "Failed to write settings to file '{}': {}",
+                path.display(),
+                error
+            ),
+        }
+    }
+}
+
+impl SettingsManager {
+    /// Loads the settings file, falling back to the defaults when it is missing or unreadable.
+    ///
+    /// A file that exists but cannot be loaded is returned as an error along with the defaults,
+    /// and is never saved over by this manager.
+    pub fn new(settings_file_path: PathBuf) -> (Self, Option<LoadSettingsError>) {
+        let load_result = match read_to_string(&settings_file_path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|error| {
+                LoadSettingsError::JsonParseError(settings_file_path.clone(), error)
+            }),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Settings::default()),
+            Err(error) => Err(LoadSettingsError::FileReadError(
+                settings_file_path.clone(),
+                error,
+            )),
+        };
+        let (settings, load_error) = match load_result {
+            Ok(settings) => (settings, None),
+            Err(load_error) => (Settings::default(), Some(load_error)),
+        };
+
+        let settings_manager = SettingsManager {
+            settings,
+            settings_file_path,
+            read_only: load_error.is_some(),
+        };
+        (settings_manager, load_error)
+    }
+
+    /// Does nothing when the file could not be loaded, the settings then only lasting the session
+    fn save_settings(&self, settings: &Settings) -> Result<(), SaveSettingsError> {
+        if self.read_only {
+            return Ok(());
+        }
+        let settings_json_string: String =
+            serde_json::to_string_pretty(settings).map_err(|error| {
+                SaveSettingsError::JsonConversionError(self.settings_file_path.clone(), error)
+            })?;
+
+        write_atomically(&self.settings_file_path, settings_json_string.as_bytes()).map_err(
+            |error| SaveSettingsError::FileWriteError(self.settings_file_path.clone(), error),
+        )
+    }
+
+    pub fn set_task_list_view(&mut self, view: TaskListView) -> Result<(), SaveSettingsError> {
+        let mut new_settings: Settings = self.settings.clone();
+        new_settings.task_list_view = view;
+
+        self.save_settings(&new_settings)?;
+
+        self.settings = new_settings;
+        Ok(())
+    }
+
+    pub fn set_selected_task_list(
+        &mut self,
+        selected_task_list: Option<String>,
+    ) -> Result<(), SaveSettingsError> {
+        let mut new_settings: Settings = self.settings.clone();
+        new_settings.selected_task_list = selected_task_list;
+
+        self.save_settings(&new_settings)?;
+
+        self.settings = new_settings;
+        Ok(())
+    }
+}
@@ -0,0 +1,553 @@
+use std::{
+    fs::{File, OpenOptions, canonicalize, copy, hard_link, read_to_string, remove_file, rename},
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    formats::todo_txt,
+    popups::task_list::Task,
+    storage::{
+        LoadTaskFileError, SaveTaskError, TaskSnapshot, TaskStore,
+        migrations::{TASK_FILE_VERSION, migrate_tasks},
+    },
+};
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum TaskFileFormat {
+    Json,
+    TodoTxt,
+}
+
+impl TaskFileFormat {
+    /// Guesses the format from the file extension, `.txt` files being todo.txt files
+    pub fn from_path(file_path: &Path) -> Self {
+        match file_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("txt") => TaskFileFormat::TodoTxt,
+            _ => TaskFileFormat::Json,
+        }
+    }
+
+    /// todo.txt tools archive into a `done.txt` next to the task file
+    pub fn archive_file_path(&self, tasks_file_path: &Path) -> PathBuf {
+        match self {
+            TaskFileFormat::Json => tasks_file_path.with_extension("archive.json"),
+            TaskFileFormat::TodoTxt => tasks_file_path.with_file_name("done.txt"),
+        }
+    }
+}
+
+/// Layout of the JSON task files since version 1, version 0 being a bare array of tasks
+#[derive(Serialize, Deserialize)]
+struct VersionedTaskFile<T> {
+    version: u64,
+    tasks: T,
+}
+
+/// Number of previous versions of the task file kept next to it
+const BACKUP_COUNT: usize = 5;
+
+/// Time between two backups of the task file, so that they span more than the last few edits
+const BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A previous version of the task file, 1 being the most recent
+#[derive(Clone)]
+pub struct TaskBackup {
+    pub number: usize,
+    pub file_path: PathBuf,
+    pub modified_at: Option<SystemTime>,
+}
+
+/// Keeps the main list in one file and the archived tasks in another, rewriting them whole
+pub struct FileTaskStore {
+    tasks_file_path: PathBuf,
+    archive_file_path: PathBuf,
+    tasks_file_format: TaskFileFormat,
+    /// Content of the archive file, to only rewrite it when the archive changes
+    saved_archived_tasks: Vec<Task>,
+    /// Stamps of the task and archive files when this store last read or wrote them
+    synced_file_stamps: [Option<FileStamp>; 2],
+    /// When this store last backed up the task file, `None` until its first save
+    backed_up_at: Option<Instant>,
+}
+
+/// Modification time and size of a file, which change when anything rewrites it
+type FileStamp = (SystemTime, u64);
+
+fn file_stamp(file_path: &Path) -> Option<FileStamp> {
+    let metadata = file_path.metadata().ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+impl FileTaskStore {
+    pub fn new(tasks_file_path: PathBuf) -> Self {
+        let tasks_file_format = TaskFileFormat::from_path(&tasks_file_path);
+        FileTaskStore {
+            archive_file_path: tasks_file_format.archive_file_path(&tasks_file_path),
+            tasks_file_path,
+            tasks_file_format,
+            saved_archived_tasks: Vec::new(),
+            synced_file_stamps: [None, None],
+            backed_up_at: None,
+        }
+    }
+
+    /// Lists the existing backups of the task file, the most recent first
+    pub fn backups(&self) -> Vec<TaskBackup> {
+        (1..=BACKUP_COUNT)
+            .map(|number| backup_file_path(&self.tasks_file_path, number))
+            .enumerate()
+            .filter_map(|(idx, file_path)| {
+                let metadata = file_path.metadata().ok()?;
+                Some(TaskBackup {
+                    number: idx + 1,
+                    modified_at: metadata.modified().ok(),
+                    file_path,
+                })
+            })
+            .collect()
+    }
+
+    pub fn load_backup(&self, backup: &TaskBackup) -> Result<Vec<Task>, LoadTaskFileError> {
+        // Unlike a missing task file, a missing backup is not an empty list
+        if !backup.file_path.exists() {
+            return Err(LoadTaskFileError::IoError(
+                backup.file_path.clone(),
+                std::io::ErrorKind::NotFound.into(),
+            ));
+        }
+        read_tasks_file(&backup.file_path, self.tasks_file_format)
+    }
+
+    fn file_stamps(&self) -> [Option<FileStamp>; 2] {
+        [
+            file_stamp(&self.tasks_file_path),
+            file_stamp(&self.archive_file_path),
+        ]
+    }
+
+    /// Writes the task file, first making the current one the most recent backup on the first
+    /// save and then once every `BACKUP_INTERVAL`
+    fn write_tasks_file_with_backup(&mut self, tasks: &[Task]) -> Result<(), SaveTaskError> {
+        if self
+            .backed_up_at
+            .is_none_or(|backed_up_at| backed_up_at.elapsed() >= BACKUP_INTERVAL)
+        {
+            rotate_backups(&self.tasks_file_path)?;
+            self.backed_up_at = Some(Instant::now());
+        }
+        write_tasks_file(&self.tasks_file_path, tasks, self.tasks_file_format)
+    }
+}
+
+impl TaskStore for FileTaskStore {
+    fn load(&mut self) -> Result<TaskSnapshot, LoadTaskFileError> {
+        // Taken first, so that a change made while reading is noticed by the next check, and
+        // kept even when reading fails, so that a broken file is only reported again once changed
+        self.synced_file_stamps = self.file_stamps();
+        let snapshot = TaskSnapshot {
+            tasks: read_tasks_file(&self.tasks_file_path, self.tasks_file_format)?,
+            archived_tasks: read_tasks_file(&self.archive_file_path, self.tasks_file_format)?,
+        };
+        self.saved_archived_tasks = snapshot.archived_tasks.clone();
+        Ok(snapshot)
+    }
+
+    /// Saves both files, putting the archive file back as it was if the task file cannot be written
+    fn save(&mut self, snapshot: &TaskSnapshot) -> Result<(), SaveTaskError> {
+        if snapshot.archived_tasks == self.saved_archived_tasks {
+            self.write_tasks_file_with_backup(&snapshot.tasks)?;
+        } else {
+            write_tasks_file(
+                &self.archive_file_path,
+                &snapshot.archived_tasks,
+                self.tasks_file_format,
+            )?;
+
+            if let Err(error) = self.write_tasks_file_with_backup(&snapshot.tasks) {
+                let _ = write_tasks_file(
+                    &self.archive_file_path,
+                    &self.saved_archived_tasks,
+                    self.tasks_file_format,
+                );
+                return Err(error);
+            }
+            self.saved_archived_tasks = snapshot.archived_tasks.clone();
+        }
+
+        self.synced_file_stamps = self.file_stamps();
+        Ok(())
+    }
+
+    fn has_changed(&mut self) -> bool {
+        let file_stamps = self.file_stamps();
+        let has_changed = file_stamps != self.synced_file_stamps;
+        self.synced_file_stamps = file_stamps;
+        has_changed
+    }
+}
+
+fn read_tasks_file(
+    file_path: &Path,
+    file_format: TaskFileFormat,
+) -> Result<Vec<Task>, LoadTaskFileError> {
+    match read_to_string(file_path) {
+        Ok(content) => match file_format {
+            TaskFileFormat::Json => parse_json_tasks(file_path, &content),
+            TaskFileFormat::TodoTxt => Ok(todo_txt::parse(&content)),
+        },
+        Err(error) => match error.kind() {
+            std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            _ => Err(LoadTaskFileError::IoError(file_path.to_path_buf(), error)),
+        },
+    }
+}
+
+/// Reads any version of the JSON task file, migrating its tasks to the current version
+fn parse_json_tasks(file_path: &Path, content: &str) -> Result<Vec<Task>, LoadTaskFileError> {
+    let parsing_error = |error| LoadTaskFileError::ParsingError(file_path.to_path_buf(), error);
+
+    let task_file: VersionedTaskFile<Vec<Value>> = match serde_json::from_str(content) {
+        Ok(Value::Array(tasks)) => VersionedTaskFile { version: 0, tasks },
+        Ok(value) => serde_json::from_value(value).map_err(parsing_error)?,
+        Err(error) => return Err(parsing_error(error)),
+    };
+    if task_file.version > TASK_FILE_VERSION {
+        return Err(LoadTaskFileError::UnsupportedVersion(
+            file_path.to_path_buf(),
+            task_file.version,
+        ));
+    }
+
+    let mut tasks = task_file.tasks;
+    migrate_tasks(&mut tasks, task_file.version);
+    serde_json::from_value(Value::Array(tasks)).map_err(parsing_error)
+}
+
+fn write_tasks_file(
+    file_path: &Path,
+    tasks: &[Task],
+    file_format: TaskFileFormat,
+) -> Result<(), SaveTaskError> {
+    let tasks_string: String = match file_format {
+        TaskFileFormat::Json => serde_json::to_string_pretty(&VersionedTaskFile {
+            version: TASK_FILE_VERSION,
+            tasks,
+        })
+        .map_err(|error| SaveTaskError::JsonConversionError(file_path.to_path_buf(), error))?,
+        TaskFileFormat::TodoTxt => todo_txt::serialize(tasks),
+    };
+
+    write_atomically(file_path, tasks_string.as_bytes())
+        .map_err(|error| SaveTaskError::FileWriteError(file_path.to_path_buf(), error))
+}
+
+fn backup_file_path(file_path: &Path, number: usize) -> PathBuf {
+    let mut file_name = file_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".bak.{}", number));
+    file_path.with_file_name(file_name)
+}
+
+/// Shifts every backup one version back, then keeps the current file as the most recent one
+fn rotate_backups(file_path: &Path) -> Result<(), SaveTaskError> {
+    if !file_path.exists() {
+        return Ok(());
+    }
+
+    for number in (1..BACKUP_COUNT).rev() {
+        let backup_path = backup_file_path(file_path, number);
+        if backup_path.exists() {
+            let older_backup_path = backup_file_path(file_path, number + 1);
+            rename(&backup_path, &older_backup_path)
+                .map_err(|error| SaveTaskError::FileWriteError(older_backup_path, error))?;
+        }
+    }
+
+    // The current file is about to be replaced by a rename, so a hard link is enough to keep it
+    let newest_backup_path = backup_file_path(file_path, 1);
+    if hard_link(resolve_links(file_path), &newest_backup_path).is_err() {
+        copy(file_path, &newest_backup_path)
+            .map_err(|error| SaveTaskError::FileWriteError(newest_backup_path, error))?;
+    }
+    Ok(())
+}
+
+/// The file a path leads to through symbolic links, or the path itself when it leads nowhere
+fn resolve_links(file_path: &Path) -> PathBuf {
+    canonicalize(file_path).unwrap_or_else(|_| file_path.to_path_buf())
+}
+
+/// Writes to a temporary file that is synced and then renamed over `file_path`, so that a crash
+/// or a full disk leaves either the previous content or the new one, never a truncated file
+///
+/// The temporary file gets a random name, so that two instances saving at once do not write to
+/// the same one, and the permissions of the file it replaces. A symbolic link is written through,
+/// its target being replaced rather than the link itself.
+pub fn write_atomically(file_path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let file_path = &resolve_links(file_path);
+    let mut temporary_file_name = file_path.file_name().unwrap_or_default().to_os_string();
+    temporary_file_name.push(format!(".{}.tmp", Uuid::new_v4()));
+    let temporary_file_path = file_path.with_file_name(temporary_file_name);
+    let permissions = file_path
+        .metadata()
+        .ok()
+        .map(|metadata| metadata.permissions());
+
+    let write_result = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temporary_file_path)
+        .and_then(|mut file| {
+            if let Some(permissions) = permissions {
+                file.set_permissions(permissions)?;
+            }
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|_| rename(&temporary_file_path, file_path));
+    if write_result.is_err() {
+        let _ = remove_file(&temporary_file_path);
+        return write_result;
+    }
+
+    // Syncing the directory makes the rename itself durable, which not every platform allows
+    let directory_path = match file_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    if let Ok(directory) = File::open(directory_path) {
+        let _ = directory.sync_all();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::storage::{TaskManager, test_task};
+
+    fn titles(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|task| task.title.as_str()).collect()
+    }
+
+    #[test]
+    fn saved_tasks_are_loaded_back_from_their_own_files() {
+        let data_dir = TempDir::new().unwrap();
+        for file_name in ["tasks.json", "todo.txt"] {
+            let tasks_file_path = data_dir.path().join(file_name);
+            let snapshot = TaskSnapshot {
+                tasks: vec![test_task("first"), test_task("second")],
+                archived_tasks: vec![test_task("archived")],
+            };
+            assert!(
+                FileTaskStore::new(tasks_file_path.clone())
+                    .save(&snapshot)
+                    .is_ok()
+            );
+
+            let mut store = FileTaskStore::new(tasks_file_path.clone());
+            let loaded_snapshot = store.load().unwrap();
+            assert_eq!(titles(&loaded_snapshot.tasks), ["first", "second"]);
+            assert_eq!(titles(&loaded_snapshot.archived_tasks), ["archived"]);
+            assert!(
+                store
+                    .tasks_file_format
+                    .archive_file_path(&tasks_file_path)
+                    .exists()
+            );
+        }
+    }
+
+    #[test]
+    fn missing_files_are_empty_lists() {
+        let data_dir = TempDir::new().unwrap();
+        let mut store = FileTaskStore::new(data_dir.path().join("tasks.json"));
+
+        let snapshot = store.load().unwrap();
+        assert!(snapshot.tasks.is_empty() && snapshot.archived_tasks.is_empty());
+    }
+
+    #[test]
+    fn changes_made_by_another_store_are_noticed() {
+        let data_dir = TempDir::new().unwrap();
+        let tasks_file_path = data_dir.path().join("tasks.json");
+        let mut store = FileTaskStore::new(tasks_file_path.clone());
+        assert!(store.load().is_ok());
+        assert!(!store.has_changed());
+
+        let mut other_store = FileTaskStore::new(tasks_file_path);
+        let snapshot = TaskSnapshot {
+            tasks: vec![test_task("first")],
+            archived_tasks: Vec::new(),
+        };
+        assert!(other_store.save(&snapshot).is_ok());
+        assert!(store.has_changed());
+        assert!(!store.has_changed());
+    }
+
+    #[test]
+    fn bare_arrays_of_version_0_are_migrated() {
+        let data_dir = TempDir::new().unwrap();
+        let tasks_file_path = data_dir.path().join("tasks.json");
+        std::fs::write(
+            &tasks_file_path,
+            r#"[{"uuid": "67e55044-10b1-426f-9247-bb680e5fe0c8", "title": "Write report", "status": "Todo"}]"#,
+        )
+        .unwrap();
+
+        let tasks = read_tasks_file(&tasks_file_path, TaskFileFormat::Json).unwrap();
+        assert_eq!(titles(&tasks), ["Write report"]);
+        assert!(tasks[0].completion_history.is_empty());
+    }
+
+    #[test]
+    fn saved_files_have_the_current_version() {
+        let data_dir = TempDir::new().unwrap();
+        let tasks_file_path = data_dir.path().join("tasks.json");
+        assert!(
+            write_tasks_file(
+                &tasks_file_path,
+                &[test_task("first")],
+                TaskFileFormat::Json
+            )
+            .is_ok()
+        );
+
+        let content: Value =
+            serde_json::from_str(&read_to_string(&tasks_file_path).unwrap()).unwrap();
+        assert_eq!(content["version"], TASK_FILE_VERSION);
+        assert_eq!(content["tasks"][0]["title"], "first");
+        let tasks = read_tasks_file(&tasks_file_path, TaskFileFormat::Json).unwrap();
+        assert_eq!(titles(&tasks), ["first"]);
+    }
+
+    #[test]
+    fn files_of_a_newer_version_are_refused() {
+        let data_dir = TempDir::new().unwrap();
+        let tasks_file_path = data_dir.path().join("tasks.json");
+        std::fs::write(
+            &tasks_file_path,
+            format!(r#"{{"version": {}, "tasks": []}}"#, TASK_FILE_VERSION + 1),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            read_tasks_file(&tasks_file_path, TaskFileFormat::Json),
+            Err(LoadTaskFileError::UnsupportedVersion(_, version)) if version == TASK_FILE_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn backups_are_made_once_per_session() {
+        let data_dir = TempDir::new().unwrap();
+        let tasks_file_path = data_dir.path().join("tasks.json");
+        let snapshot = |titles: &[&str]| TaskSnapshot {
+            tasks: titles.iter().map(|title| test_task(title)).collect(),
+            archived_tasks: Vec::new(),
+        };
+        assert!(
+            FileTaskStore::new(tasks_file_path.clone())
+                .save(&snapshot(&["first"]))
+                .is_ok()
+        );
+
+        let mut store = FileTaskStore::new(tasks_file_path.clone());
+        assert!(store.save(&snapshot(&["second"])).is_ok());
+        assert!(store.save(&snapshot(&["third"])).is_ok());
+        assert!(
+            FileTaskStore::new(tasks_file_path.clone())
+                .save(&snapshot(&["fourth"]))
+                .is_ok()
+        );
+
+        let backups = store.backups();
+        let backup_titles: Vec<Vec<String>> = backups
+            .iter()
+            .map(|backup| {
+                store
+                    .load_backup(backup)
+                    .unwrap()
+                    .into_iter()
+                    .map(|task| task.title)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(backup_titles, [["third"], ["first"]]);
+    }
+
+    #[test]
+    fn atomic_writes_replace_the_file_and_leave_nothing_else() {
+        let data_dir = TempDir::new().unwrap();
+        let file_path = data_dir.path().join("tasks.json");
+        assert!(write_atomically(&file_path, b"first").is_ok());
+        assert!(write_atomically(&file_path, b"second").is_ok());
+
+        assert_eq!(read_to_string(&file_path).unwrap(), "second");
+        assert_eq!(std::fs::read_dir(data_dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_writes_keep_links_and_permissions() {
+        use std::os::unix::fs::{PermissionsExt, symlink};
+
+        let (data_dir, other_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let target_path = other_dir.path().join("tasks.json");
+        let link_path = data_dir.path().join("tasks.json");
+        std::fs::write(&target_path, "first").unwrap();
+        std::fs::set_permissions(&target_path, std::fs::Permissions::from_mode(0o640)).unwrap();
+        symlink(&target_path, &link_path).unwrap();
+
+        assert!(write_atomically(&link_path, b"second").is_ok());
+        assert!(
+            link_path
+                .symlink_metadata()
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(read_to_string(&target_path).unwrap(), "second");
+        let target_mode = target_path.metadata().unwrap().permissions().mode();
+        assert_eq!(target_mode & 0o777, 0o640);
+    }
+
+    #[test]
+    fn backups_restore_over_an_unreadable_file() {
+        let data_dir = TempDir::new().unwrap();
+        let tasks_file_path = data_dir.path().join("tasks.json");
+        let snapshot = TaskSnapshot {
+            tasks: vec![test_task("first")],
+            archived_tasks: Vec::new(),
+        };
+        assert!(
+            FileTaskStore::new(tasks_file_path.clone())
+                .save(&snapshot)
+                .is_ok()
+        );
+        assert!(
+            FileTaskStore::new(tasks_file_path.clone())
+                .save(&snapshot)
+                .is_ok()
+        );
+        std::fs::write(&tasks_file_path, "{").unwrap();
+
+        let store = FileTaskStore::new(tasks_file_path.clone());
+        let backup_tasks = store.load_backup(&store.backups()[0]).unwrap();
+        let mut task_manager = TaskManager::with_store_or_empty(Box::new(store));
+        assert!(task_manager.replace_tasks(backup_tasks).is_ok());
+
+        let tasks = read_tasks_file(&tasks_file_path, TaskFileFormat::Json).unwrap();
+        assert_eq!(titles(&tasks), ["first"]);
+    }
+}
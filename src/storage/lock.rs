@@ -0,0 +1,58 @@
+use std::{
+    fmt::Display,
+    fs::{File, OpenOptions, TryLockError},
+    path::{Path, PathBuf},
+};
+
+/// Advisory lock making the holder the only instance allowed to write the data files,
+/// released when dropped or when the process exits
+pub struct DataLock {
+    _lock_file: File,
+}
+
+pub enum LockDataError {
+    /// Another instance holds the lock
+    HeldElsewhere(PathBuf),
+    IoError(PathBuf, std::io::Error),
+}
+
+impl Display for LockDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockDataError::HeldElsewhere(path) => write!(
+                f,
+                "Another instance is using the data files, as locked by {}",
+                path.display()
+            ),
+            LockDataError::IoError(path, error) => write!(
+                f,
+                "Failed to lock the data files with {}: {}",
+                path.display(),
+                error
+            ),
+        }
+    }
+}
+
+impl DataLock {
+    pub fn acquire(lock_file_path: &Path) -> Result<Self, LockDataError> {
+        let lock_file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_file_path)
+            .map_err(|error| LockDataError::IoError(lock_file_path.to_path_buf(), error))?;
+
+        match lock_file.try_lock() {
+            Ok(()) => Ok(DataLock {
+                _lock_file: lock_file,
+            }),
+            Err(TryLockError::WouldBlock) => {
+                Err(LockDataError::HeldElsewhere(lock_file_path.to_path_buf()))
+            }
+            Err(TryLockError::Error(error)) => {
+                Err(LockDataError::IoError(lock_file_path.to_path_buf(), error))
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::storage::{LoadTaskFileError, SaveTaskError, TaskSnapshot, TaskStore};
+
+/// Keeps the tasks in memory only, for tests
+#[derive(Default)]
+pub struct MemoryTaskStore {
+    snapshot: Rc<RefCell<TaskSnapshot>>,
+    /// Tasks as this store last loaded, saved or checked them
+    seen_snapshot: TaskSnapshot,
+}
+
+impl MemoryTaskStore {
+    pub fn new(snapshot: TaskSnapshot) -> Self {
+        MemoryTaskStore {
+            snapshot: Rc::new(RefCell::new(snapshot)),
+            seen_snapshot: TaskSnapshot::default(),
+        }
+    }
+
+    /// Another store over the same tasks, as a second instance of the app would open them
+    pub fn share(&self) -> Self {
+        MemoryTaskStore {
+            snapshot: self.snapshot.clone(),
+            seen_snapshot: self.snapshot.borrow().clone(),
+        }
+    }
+}
+
+impl TaskStore for MemoryTaskStore {
+    fn load(&mut self) -> Result<TaskSnapshot, LoadTaskFileError> {
+        self.seen_snapshot = self.snapshot.borrow().clone();
+        Ok(self.seen_snapshot.clone())
+    }
+
+    fn save(&mut self, snapshot: &TaskSnapshot) -> Result<(), SaveTaskError> {
+        *self.snapshot.borrow_mut() = snapshot.clone();
+        self.seen_snapshot = snapshot.clone();
+        Ok(())
+    }
+
+    fn has_changed(&mut self) -> bool {
+        let snapshot = self.snapshot.borrow();
+        let has_changed = snapshot.tasks != self.seen_snapshot.tasks
+            || snapshot.archived_tasks != self.seen_snapshot.archived_tasks;
+        self.seen_snapshot = snapshot.clone();
+        has_changed
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::{popups::task_list::Task, storage::TaskSnapshot};
+
+/// Where a task sits in a snapshot
+#[derive(PartialEq)]
+enum Placement<'a> {
+    Main(&'a Task),
+    Archived(&'a Task),
+}
+
+fn placements(snapshot: &TaskSnapshot) -> HashMap<Uuid, Placement<'_>> {
+    let main_tasks = snapshot
+        .tasks
+        .iter()
+        .map(|task| (task.uuid, Placement::Main(task)));
+    let archived_tasks = snapshot
+        .archived_tasks
+        .iter()
+        .map(|task| (task.uuid, Placement::Archived(task)));
+    main_tasks.chain(archived_tasks).collect()
+}
+
+/// Combines our changes and the ones made to the stored tasks since they were both `base`,
+/// task by task, returning the tasks changed differently on both sides when they cannot be merged
+pub fn merge_snapshots(
+    base: &TaskSnapshot,
+    ours: &TaskSnapshot,
+    theirs: &TaskSnapshot,
+) -> Result<TaskSnapshot, Vec<Uuid>> {
+    let (base_placements, our_placements, their_placements) =
+        (placements(base), placements(ours), placements(theirs));
+
+    let mut merged_placements: HashMap<Uuid, &Placement> = HashMap::new();
+    let mut conflicting_task_uuids: Vec<Uuid> = Vec::new();
+    for task_uuid in our_placements.keys().chain(their_placements.keys()) {
+        let (base_placement, our_placement, their_placement) = (
+            base_placements.get(task_uuid),
+            our_placements.get(task_uuid),
+            their_placements.get(task_uuid),
+        );
+        let merged_placement =
+            if our_placement == base_placement || our_placement == their_placement {
+                their_placement
+            } else if their_placement == base_placement {
+                our_placement
+            } else {
+                if !conflicting_task_uuids.contains(task_uuid) {
+                    conflicting_task_uuids.push(*task_uuid);
+                }
+                continue;
+            };
+        if let Some(merged_placement) = merged_placement {
+            merged_placements.insert(*task_uuid, merged_placement);
+        }
+    }
+    if !conflicting_task_uuids.is_empty() {
+        return Err(conflicting_task_uuids);
+    }
+
+    // The side that reordered a list decides its order, tasks only known to the other side last
+    let ordered = |base_tasks: &[Task], our_tasks: &[Task], their_tasks: &[Task]| {
+        let uuids = |tasks: &[Task]| tasks.iter().map(|task| task.uuid).collect::<Vec<Uuid>>();
+        let (leading_tasks, trailing_tasks) = if uuids(our_tasks) != uuids(base_tasks) {
+            (our_tasks, their_tasks)
+        } else {
+            (their_tasks, our_tasks)
+        };
+        let mut task_uuids = uuids(leading_tasks);
+        for task_uuid in uuids(trailing_tasks) {
+            if !task_uuids.contains(&task_uuid) {
+                task_uuids.push(task_uuid);
+            }
+        }
+        task_uuids
+    };
+
+    let mut merged = TaskSnapshot::default();
+    for task_uuid in ordered(&base.tasks, &ours.tasks, &theirs.tasks) {
+        if let Some(Placement::Main(task)) = merged_placements.get(&task_uuid) {
+            merged.tasks.push((*task).clone());
+        }
+    }
+    for task_uuid in ordered(
+        &base.archived_tasks,
+        &ours.archived_tasks,
+        &theirs.archived_tasks,
+    ) {
+        if let Some(Placement::Archived(task)) = merged_placements.get(&task_uuid) {
+            merged.archived_tasks.push((*task).clone());
+        }
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_task;
+
+    fn snapshot(tasks: &[&Task]) -> TaskSnapshot {
+        TaskSnapshot {
+            tasks: tasks.iter().map(|task| (*task).clone()).collect(),
+            archived_tasks: Vec::new(),
+        }
+    }
+
+    fn renamed(task: &Task, title: &str) -> Task {
+        Task {
+            title: title.to_string(),
+            ..task.clone()
+        }
+    }
+
+    #[test]
+    fn changes_to_different_tasks_are_merged() {
+        let (first_task, second_task, third_task) =
+            (test_task("first"), test_task("second"), test_task("third"));
+        let base = snapshot(&[&first_task, &second_task, &third_task]);
+        let ours = snapshot(&[&renamed(&first_task, "ours"), &second_task, &third_task]);
+        let their_task = test_task("theirs");
+        let theirs = snapshot(&[&first_task, &third_task, &their_task]);
+
+        let merged = merge_snapshots(&base, &ours, &theirs).unwrap_or_default();
+        let merged_titles: Vec<&str> = merged
+            .tasks
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect();
+        assert_eq!(merged_titles, ["ours", "third", "theirs"]);
+    }
+
+    #[test]
+    fn the_same_task_changed_on_both_sides_conflicts() {
+        let (first_task, second_task) = (test_task("first"), test_task("second"));
+        let base = snapshot(&[&first_task, &second_task]);
+        let ours = snapshot(&[&renamed(&first_task, "ours"), &second_task]);
+        let theirs = snapshot(&[&renamed(&first_task, "theirs"), &second_task]);
+
+        assert!(matches!(
+            merge_snapshots(&base, &ours, &theirs),
+            Err(conflicting_task_uuids) if conflicting_task_uuids == [first_task.uuid]
+        ));
+        let same_change = snapshot(&[&renamed(&first_task, "both"), &second_task]);
+        assert!(merge_snapshots(&base, &same_change, &same_change).is_ok());
+    }
+
+    #[test]
+    fn archiving_on_one_side_moves_the_task() {
+        let (first_task, second_task) = (test_task("first"), test_task("second"));
+        let base = snapshot(&[&first_task, &second_task]);
+        let ours = snapshot(&[&first_task, &renamed(&second_task, "ours")]);
+        let theirs = TaskSnapshot {
+            tasks: vec![second_task.clone()],
+            archived_tasks: vec![first_task.clone()],
+        };
+
+        let merged = merge_snapshots(&base, &ours, &theirs).unwrap_or_default();
+        assert_eq!(merged.tasks.len(), 1);
+        assert_eq!(merged.tasks[0].title, "ours");
+        assert_eq!(merged.archived_tasks.len(), 1);
+        assert_eq!(merged.archived_tasks[0].uuid, first_task.uuid);
+    }
+}
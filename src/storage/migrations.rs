@@ -0,0 +1,112 @@
+use serde_json::{Map, Value};
+
+/// Version written in the task files, to bump with a new migration whenever `Task` changes
+pub const TASK_FILE_VERSION: u64 = 7;
+
+/// Upgrades one task from each version to the next, `MIGRATIONS[0]` upgrading version 0 to 1
+const MIGRATIONS: [fn(&mut Map<String, Value>); TASK_FILE_VERSION as usize] = [
+    add_optional_fields,
+    add_list,
+    add_recurrence,
+    add_activity,
+    add_notes,
+    add_dependencies,
+    add_planning,
+];
+
+/// Brings tasks written with an older file version up to `TASK_FILE_VERSION`
+pub fn migrate_tasks(tasks: &mut [Value], version: u64) {
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        for task in tasks.iter_mut() {
+            if let Value::Object(task) = task {
+                migration(task);
+            }
+        }
+    }
+}
+
+/// Version 0, a bare array of tasks, predates these fields and may lack them
+fn add_optional_fields(task: &mut Map<String, Value>) {
+    for field_name in ["created_at", "priority", "completed_at"] {
+        task.entry(field_name).or_insert(Value::Null);
+    }
+    task.entry("tags").or_insert(Value::Array(Vec::new()));
+}
+
+/// Version 2 puts tasks in named lists, the ones from earlier versions going to the default list
+fn add_list(task: &mut Map<String, Value>) {
+    task.entry("list").or_insert(Value::Null);
+}
+
+/// Version 3 adds recurring tasks and the history of their completions
+fn add_recurrence(task: &mut Map<String, Value>) {
+    task.entry("recurrence").or_insert(Value::Null);
+    task.entry("completion_history")
+        .or_insert(Value::Array(Vec::new()));
+}
+
+/// Version 4 records when tasks were last changed and which statuses they went through
+fn add_activity(task: &mut Map<String, Value>) {
+    task.entry("updated_at").or_insert(Value::Null);
+    task.entry("status_history")
+        .or_insert(Value::Array(Vec::new()));
+}
+
+/// Version 5 adds notes to the tasks
+fn add_notes(task: &mut Map<String, Value>) {
+    task.entry("notes").or_insert(Value::String(String::new()));
+}
+
+/// Version 6 lets tasks be blocked by other tasks
+fn add_dependencies(task: &mut Map<String, Value>) {
+    task.entry("blocked_by").or_insert(Value::Array(Vec::new()));
+}
+
+/// Version 7 adds an estimate in focus sessions and a due date to the tasks
+fn add_planning(task: &mut Map<String, Value>) {
+    for field_name in ["estimate", "due_date"] {
+        task.entry(field_name).or_insert(Value::Null);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::popups::task_list::Task;
+
+    #[test]
+    fn tasks_from_version_0_get_every_field() {
+        let mut tasks = vec![json!({
+            "uuid": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "title": "Write report",
+            "status": "Todo",
+        })];
+        migrate_tasks(&mut tasks, 0);
+
+        let task: Option<Task> = serde_json::from_value(tasks.remove(0)).ok();
+        assert!(task.is_some_and(|task| task.title == "Write report"
+            && task.notes.is_empty()
+            && task.blocked_by.is_empty()
+            && task.estimate.is_none()));
+    }
+
+    #[test]
+    fn migrations_keep_the_fields_already_there() {
+        let mut tasks = vec![json!({ "notes": "kept", "list": "Work" })];
+        migrate_tasks(&mut tasks, 0);
+
+        assert_eq!(tasks[0]["notes"], "kept");
+        assert_eq!(tasks[0]["list"], "Work");
+        assert_eq!(tasks[0]["due_date"], Value::Null);
+    }
+
+    #[test]
+    fn up_to_date_tasks_are_left_as_they_are() {
+        let mut tasks = vec![json!({ "title": "Write report" })];
+        migrate_tasks(&mut tasks, TASK_FILE_VERSION);
+
+        assert_eq!(tasks[0], json!({ "title": "Write report" }));
+    }
+}
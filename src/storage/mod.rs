@@ -0,0 +1,1140 @@
+pub mod file;
+pub mod lock;
+#[cfg(test)]
+mod memory;
+mod merge;
+pub mod migrations;
+pub mod read_only;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Local, Utc};
+use uuid::Uuid;
+
+use crate::{
+    popups::task_list::{StatusChange, Task, TaskStatus},
+    recurrence::Recurrence,
+    storage::merge::merge_snapshots,
+};
+
+/// Where `TaskManager` loads its tasks from and saves them to
+///
+/// Every method saving a change receives the whole state after the change, so stores that can
+/// only rewrite everything can fall back to `save`, which is what the default methods do.
+pub trait TaskStore {
+    fn load(&mut self) -> Result<TaskSnapshot, LoadTaskFileError>;
+    fn save(&mut self, snapshot: &TaskSnapshot) -> Result<(), SaveTaskError>;
+
+    /// Tells whether something else changed the stored tasks since this store last loaded,
+    /// saved or checked them
+    fn has_changed(&mut self) -> bool {
+        false
+    }
+
+    /// Saves a task appended to the end of the main list
+    fn add_task(&mut self, _task: &Task, snapshot: &TaskSnapshot) -> Result<(), SaveTaskError> {
+        self.save(snapshot)
+    }
+
+    /// Saves new values for a task that kept its position
+    fn update_task(&mut self, _task: &Task, snapshot: &TaskSnapshot) -> Result<(), SaveTaskError> {
+        self.save(snapshot)
+    }
+
+    /// Saves the removal of a task from the main list
+    fn delete_task(
+        &mut self,
+        _task_uuid: Uuid,
+        snapshot: &TaskSnapshot,
+    ) -> Result<(), SaveTaskError> {
+        self.save(snapshot)
+    }
+}
+
+pub struct TaskManager {
+    pub tasks: Vec<Task>,
+    /// Archived tasks live in their own file and never show up in `tasks`
+    pub archived_tasks: Vec<Task>,
+    store: Box<dyn TaskStore>,
+    /// List the task list and the active task are scoped to, `None` being the default list
+    pub selected_list: Option<String>,
+    /// Task being worked on in each list, credited with the focus sessions
+    active_task_uuids: HashMap<Option<String>, Uuid>,
+    undo_stack: Vec<TaskSnapshot>,
+    redo_stack: Vec<TaskSnapshot>,
+    /// Change that could not be merged with the stored tasks, until the user picks a side
+    pending_conflict: Option<TaskConflict>,
+}
+
+/// Our version and the stored version of the tasks, both changed since they were last in sync
+struct TaskConflict {
+    ours: TaskSnapshot,
+    theirs: TaskSnapshot,
+}
+
+/// Number of changes that can be undone
+const HISTORY_LIMIT: usize = 100;
+
+/// State of both task collections before or after a change, kept for undo and redo
+#[derive(Clone, Default)]
+pub struct TaskSnapshot {
+    pub tasks: Vec<Task>,
+    pub archived_tasks: Vec<Task>,
+}
+
+/// A change touching a single task, which stores may save without rewriting everything
+enum TaskChange<'a> {
+    Added(&'a Task),
+    Updated(&'a Task),
+    Deleted(Uuid),
+}
+
+impl<'a> TaskChange<'a> {
+    /// Returns `None` when the snapshots differ by more than one task or by the archive
+    fn between(
+        previous_tasks: &[Task],
+        previous_archived_tasks: &[Task],
+        next: &'a TaskSnapshot,
+    ) -> Option<TaskChange<'a>> {
+        if previous_archived_tasks != next.archived_tasks {
+            return None;
+        }
+        let (previous_len, next_len) = (previous_tasks.len(), next.tasks.len());
+
+        if next_len == previous_len + 1 && next.tasks[..previous_len] == *previous_tasks {
+            return next.tasks.last().map(TaskChange::Added);
+        }
+        if previous_len == next_len + 1 {
+            let deleted_idx = (0..previous_len)
+                .find(|idx| previous_tasks.get(*idx) != next.tasks.get(*idx))
+                .unwrap_or(next_len);
+            let deleted_task = &previous_tasks[deleted_idx];
+            return (previous_tasks[deleted_idx + 1..] == next.tasks[deleted_idx..])
+                .then_some(TaskChange::Deleted(deleted_task.uuid));
+        }
+        if previous_len == next_len {
+            let mut changed_tasks = previous_tasks
+                .iter()
+                .zip(next.tasks.iter())
+                .filter(|(previous_task, next_task)| previous_task != next_task);
+            if let (Some((previous_task, next_task)), None) =
+                (changed_tasks.next(), changed_tasks.next())
+            {
+                return (previous_task.uuid == next_task.uuid)
+                    .then_some(TaskChange::Updated(next_task));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadTaskFileError {
+    IoError(PathBuf, std::io::Error),
+    ParsingError(PathBuf, serde_json::Error),
+    /// The file was written by a newer version of the app
+    UnsupportedVersion(PathBuf, u64),
+    #[cfg(feature = "sqlite")]
+    Database(PathBuf, rusqlite::Error),
+}
+
+impl LoadTaskFileError {
+    pub fn file_path(&self) -> &Path {
+        match self {
+            LoadTaskFileError::IoError(path, _)
+            | LoadTaskFileError::ParsingError(path, _)
+            | LoadTaskFileError::UnsupportedVersion(path, _) => path,
+            #[cfg(feature = "sqlite")]
+            LoadTaskFileError::Database(path, _) => path,
+        }
+    }
+}
+
+impl Display for LoadTaskFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadTaskFileError::IoError(path, error) => write!(
+                f,
+                "An I/O error occurred while reading the file at {}: {}",
+                path.display(),
+                error
+            ),
+            LoadTaskFileError::ParsingError(path, error) => write!(
+                f,
+                "JSON parsing error in task file at {}: {}",
+                path.display(),
+                error
+            ),
+            LoadTaskFileError::UnsupportedVersion(path, version) => write!(
+                f,
+                "The task file at {} has version {}, this version of the app only reads up to {}",
+                path.display(),
+                version,
+                migrations::TASK_FILE_VERSION
+            ),
+            #[cfg(feature = "sqlite")]
+            LoadTaskFileError::Database(path, error) => write!(
+                f,
+                "Database error while reading the tasks at {}: {}",
+                path.display(),
+                error
+            ),
+        }
+    }
+}
+
+pub enum SaveTaskError {
+    JsonConversionError(PathBuf, serde_json::Error),
+    FileWriteError(PathBuf, std::io::Error),
+    /// The tasks changed elsewhere could not be reloaded to merge them
+    Reload(LoadTaskFileError),
+    /// Number of tasks changed both here and elsewhere
+    Conflict(usize),
+    ReadOnly,
+    /// Titles of tasks that would each be blocked by the next one, the last by the first
+    DependencyCycle(Vec<String>),
+    #[cfg(feature = "sqlite")]
+    Database(PathBuf, rusqlite::Error),
+}
+
+impl Display for SaveTaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveTaskError::JsonConversionError(path, error) => {
+                write!(
+                    f,
+                    "Failed to serialize tasks to JSON for file '{}': {}",
+                    path.display(),
+                    error
+                )
+            }
+            SaveTaskError::FileWriteError(path, error) => {
+                if error.kind() == std::io::ErrorKind::PermissionDenied {
+                    write!(
+                        f,
+                        "Permission denied when writing to '{}'. Check file permissions.",
+                        path.display()
+                    )
+                } else if error.kind() == std::io::ErrorKind::NotFound {
+                    write!(
+                        f,
+                        "Cannot write to '{}': parent directory does not exist",
+                        path.display()
+                    )
+                } else {
+                    write!(
+                        f,
+                        "Failed to write tasks to file '{}': {}",
+                        path.display(),
+                        error
+                    )
+                }
+            }
+            SaveTaskError::Reload(error) => write!(
+                f,
+                "The tasks were changed elsewhere and could not be reloaded: {}",
+                error
+            ),
+            SaveTaskError::Conflict(task_count) => write!(
+                f,
+                "{} task(s) were changed both here and elsewhere since they were loaded",
+                task_count
+            ),
+            SaveTaskError::ReadOnly => write!(
+                f,
+                "The tasks were opened read-only, so changes cannot be saved"
+            ),
+            SaveTaskError::DependencyCycle(task_titles) => write!(
+                f,
+                "Tasks cannot block each other in a loop: {} -> {}",
+                task_titles.join(" -> "),
+                task_titles.first().map(String::as_str).unwrap_or_default()
+            ),
+            #[cfg(feature = "sqlite")]
+            SaveTaskError::Database(path, error) => write!(
+                f,
+                "Failed to save tasks to the database at '{}': {}",
+                path.display(),
+                error
+            ),
+        }
+    }
+}
+
+impl TaskManager {
+    pub fn with_store(store: Box<dyn TaskStore>) -> Result<Self, LoadTaskFileError> {
+        let mut manager = TaskManager {
+            tasks: Vec::new(),
+            archived_tasks: Vec::new(),
+            store,
+            selected_list: None,
+            active_task_uuids: HashMap::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            pending_conflict: None,
+        };
+
+        manager.load_tasks()?;
+        Ok(manager)
+    }
+
+    /// Starts without the stored tasks when they cannot be loaded, such as a broken file opened
+    /// read-only, which `reload_if_changed` picks up once fixed
+    pub fn with_store_or_empty(mut store: Box<dyn TaskStore>) -> Self {
+        let snapshot = store.load().unwrap_or_default();
+        TaskManager {
+            tasks: snapshot.tasks,
+            archived_tasks: snapshot.archived_tasks,
+            store,
+            selected_list: None,
+            active_task_uuids: HashMap::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            pending_conflict: None,
+        }
+    }
+
+    /// Active task of the selected list
+    pub fn active_task(&self) -> Option<&Task> {
+        let active_task_uuid = self.active_task_uuids.get(&self.selected_list)?;
+        self.tasks
+            .iter()
+            .find(|task| task.uuid == *active_task_uuid && task.list == self.selected_list)
+    }
+
+    /// Makes a task the active one of the selected list, or leaves the list without one when
+    /// it already was
+    pub fn toggle_active_task(&mut self, task_uuid: Uuid) {
+        if self.active_task().map(|task| task.uuid) == Some(task_uuid) {
+            self.active_task_uuids.remove(&self.selected_list);
+        } else {
+            self.active_task_uuids
+                .insert(self.selected_list.clone(), task_uuid);
+        }
+    }
+
+    /// The default list, then the lists found in the tasks and the archive, along with the
+    /// selected list even when it has no task yet
+    pub fn list_names(&self) -> Vec<Option<String>> {
+        let mut list_names: Vec<Option<String>> = vec![None];
+        let task_lists = self
+            .tasks
+            .iter()
+            .chain(self.archived_tasks.iter())
+            .map(|task| &task.list);
+        for list in task_lists.chain([&self.selected_list]) {
+            if !list_names.contains(list) {
+                list_names.push(list.clone());
+            }
+        }
+        list_names
+    }
+
+    fn load_tasks(&mut self) -> Result<(), LoadTaskFileError> {
+        let snapshot = self.store.load()?;
+        self.tasks = snapshot.tasks;
+        self.archived_tasks = snapshot.archived_tasks;
+        Ok(())
+    }
+
+    fn snapshot(&self) -> TaskSnapshot {
+        TaskSnapshot {
+            tasks: self.tasks.clone(),
+            archived_tasks: self.archived_tasks.clone(),
+        }
+    }
+
+    /// Replaces the tasks with the stored ones when they were changed elsewhere, forgetting
+    /// the history that would undo those changes
+    pub fn reload_if_changed(&mut self) -> Result<bool, LoadTaskFileError> {
+        if !self.store.has_changed() {
+            return Ok(false);
+        }
+        let snapshot = self.store.load()?;
+        if snapshot.tasks == self.tasks && snapshot.archived_tasks == self.archived_tasks {
+            return Ok(false);
+        }
+
+        self.tasks = snapshot.tasks;
+        self.archived_tasks = snapshot.archived_tasks;
+        self.clear_history();
+        Ok(true)
+    }
+
+    /// Describes tasks blocking each other in a loop, which edits made outside of the app can
+    /// leave in the stored tasks
+    pub fn dependency_cycle_warning(&self) -> Option<String> {
+        let cycle = dependency_cycle(&self.tasks, &[])?;
+        let task_titles: Vec<&str> = cycle.iter().map(|task| task.title.as_str()).collect();
+        Some(format!(
+            "Some tasks block each other in a loop: {} -> {}. Remove one of these blockers so \
+             that they can be started.",
+            task_titles.join(" -> "),
+            task_titles[0]
+        ))
+    }
+
+    fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// Saves the new state and only then replaces the in-memory one
+    ///
+    /// When the stored tasks changed elsewhere, both changes are merged and `true` is returned,
+    /// since the history no longer matches what is stored.
+    fn save_snapshot(&mut self, snapshot: TaskSnapshot) -> Result<bool, SaveTaskError> {
+        if self.store.has_changed() {
+            let theirs = self.store.load().map_err(SaveTaskError::Reload)?;
+            let merged = match merge_snapshots(&self.snapshot(), &snapshot, &theirs) {
+                Ok(merged) => merged,
+                Err(conflicting_task_uuids) => {
+                    self.pending_conflict = Some(TaskConflict {
+                        ours: snapshot,
+                        theirs,
+                    });
+                    return Err(SaveTaskError::Conflict(conflicting_task_uuids.len()));
+                }
+            };
+            // Each side may have added a blocker that only makes a loop along with the other's
+            if let Some(cycle) = dependency_cycle(&merged.tasks, &theirs.tasks) {
+                return Err(SaveTaskError::DependencyCycle(
+                    cycle.iter().map(|task| task.title.clone()).collect(),
+                ));
+            }
+            self.store.save(&merged)?;
+
+            self.tasks = merged.tasks;
+            self.archived_tasks = merged.archived_tasks;
+            return Ok(true);
+        }
+
+        match TaskChange::between(&self.tasks, &self.archived_tasks, &snapshot) {
+            Some(TaskChange::Added(task)) => self.store.add_task(task, &snapshot)?,
+            Some(TaskChange::Updated(task)) => self.store.update_task(task, &snapshot)?,
+            Some(TaskChange::Deleted(task_uuid)) => self.store.delete_task(task_uuid, &snapshot)?,
+            None => self.store.save(&snapshot)?,
+        }
+
+        self.tasks = snapshot.tasks;
+        self.archived_tasks = snapshot.archived_tasks;
+        Ok(false)
+    }
+
+    /// Saves a change made by the user, making it undoable
+    fn commit(&mut self, mut snapshot: TaskSnapshot) -> Result<(), SaveTaskError> {
+        // Nothing to save nor to undo
+        if snapshot.tasks == self.tasks && snapshot.archived_tasks == self.archived_tasks {
+            return Ok(());
+        }
+        if let Some(cycle) = dependency_cycle(&snapshot.tasks, &self.tasks) {
+            return Err(SaveTaskError::DependencyCycle(
+                cycle.iter().map(|task| task.title.clone()).collect(),
+            ));
+        }
+        let previous_snapshot = self.snapshot();
+        self.stamp_changes(&mut snapshot);
+        if self.save_snapshot(snapshot)? {
+            self.clear_history();
+            return Ok(());
+        }
+
+        self.undo_stack.push(previous_snapshot);
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+        Ok(())
+    }
+
+    /// Records when the tasks changed by the snapshot were last updated, and the status they left
+    fn stamp_changes(&self, snapshot: &mut TaskSnapshot) {
+        let now = Utc::now();
+        let previous_tasks: HashMap<Uuid, &Task> = self
+            .tasks
+            .iter()
+            .chain(self.archived_tasks.iter())
+            .map(|task| (task.uuid, task))
+            .collect();
+
+        for task in snapshot
+            .tasks
+            .iter_mut()
+            .chain(snapshot.archived_tasks.iter_mut())
+        {
+            let previous_task = previous_tasks.get(&task.uuid);
+            if previous_task.is_some_and(|previous_task| *previous_task == task) {
+                continue;
+            }
+            task.updated_at = Some(now);
+            if let Some(previous_task) = previous_task
+                && previous_task.status != task.status
+            {
+                task.status_history.push(StatusChange {
+                    at: now,
+                    from: previous_task.status.clone(),
+                    to: task.status.clone(),
+                });
+            }
+        }
+    }
+
+    pub fn has_conflict(&self) -> bool {
+        self.pending_conflict.is_some()
+    }
+
+    /// Settles a conflicting change, by saving our version over the stored one or by dropping it
+    pub fn resolve_conflict(&mut self, keep_ours: bool) -> Result<(), SaveTaskError> {
+        let Some(conflict) = self.pending_conflict.take() else {
+            return Ok(());
+        };
+        let snapshot = if keep_ours {
+            if let Err(error) = self.store.save(&conflict.ours) {
+                self.pending_conflict = Some(conflict);
+                return Err(error);
+            }
+            conflict.ours
+        } else {
+            conflict.theirs
+        };
+
+        self.tasks = snapshot.tasks;
+        self.archived_tasks = snapshot.archived_tasks;
+        self.clear_history();
+        Ok(())
+    }
+
+    /// Reverts the last change, returning `false` when there is nothing to undo
+    pub fn undo(&mut self) -> Result<bool, SaveTaskError> {
+        let Some(snapshot) = self.undo_stack.pop() else {
+            return Ok(false);
+        };
+        let current_snapshot = self.snapshot();
+
+        match self.save_snapshot(snapshot.clone()) {
+            Ok(true) => self.clear_history(),
+            Ok(false) => self.redo_stack.push(current_snapshot),
+            Err(error) => {
+                self.undo_stack.push(snapshot);
+                return Err(error);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Re-applies the last undone change, returning `false` when there is nothing to redo
+    pub fn redo(&mut self) -> Result<bool, SaveTaskError> {
+        let Some(snapshot) = self.redo_stack.pop() else {
+            return Ok(false);
+        };
+        let current_snapshot = self.snapshot();
+
+        match self.save_snapshot(snapshot.clone()) {
+            Ok(true) => self.clear_history(),
+            Ok(false) => self.undo_stack.push(current_snapshot),
+            Err(error) => {
+                self.redo_stack.push(snapshot);
+                return Err(error);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Replaces the main list, for instance with a backup, leaving the archive as it is
+    pub fn replace_tasks(&mut self, tasks: Vec<Task>) -> Result<(), SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        new_snapshot.tasks = tasks;
+
+        // Saved even when nothing seems to change, as the stored tasks may be unreadable
+        if new_snapshot.tasks == self.tasks {
+            return self.save_snapshot(new_snapshot).map(|_| ());
+        }
+        self.commit(new_snapshot)
+    }
+
+    /// Appends tasks read from another file, skipping the ones already in the list or the
+    /// archive
+    ///
+    /// Files of other apps have no uuids, so a task with the same title, list and creation date
+    /// as a known one is taken to be that task, which makes importing the same file again add
+    /// nothing.
+    pub fn import_tasks(&mut self, tasks: Vec<Task>) -> Result<usize, SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        let mut imported_count: usize = 0;
+        for task in tasks {
+            let is_known = new_snapshot
+                .tasks
+                .iter()
+                .chain(&new_snapshot.archived_tasks)
+                .any(|task_element| task_element.uuid == task.uuid)
+                || self
+                    .tasks
+                    .iter()
+                    .chain(&self.archived_tasks)
+                    .any(|task_element| {
+                        task_element.title == task.title
+                            && task_element.list == task.list
+                            && task_element.created_at == task.created_at
+                    });
+            if !is_known {
+                new_snapshot.tasks.push(task);
+                imported_count += 1;
+            }
+        }
+
+        self.commit(new_snapshot)?;
+        Ok(imported_count)
+    }
+
+    /// Adds a task at the end of the selected list, returning its index
+    pub fn add_task(&mut self, mut task: Task) -> Result<usize, SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        task.list = self.selected_list.clone();
+        new_snapshot.tasks.push(task);
+
+        self.commit(new_snapshot)?;
+
+        let idx = self.tasks.len() - 1;
+        Ok(idx)
+    }
+
+    pub fn delete_tasks(&mut self, task_uuids: &[Uuid]) -> Result<(), SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        new_snapshot
+            .tasks
+            .retain(|task_element| !task_uuids.contains(&task_element.uuid));
+
+        self.commit(new_snapshot)
+    }
+
+    /// Saves a change that may touch task statuses, returning the tasks it completed
+    fn commit_status_change(
+        &mut self,
+        mut snapshot: TaskSnapshot,
+    ) -> Result<Vec<Uuid>, SaveTaskError> {
+        let mut completed_task_uuids: Vec<Uuid> = Vec::new();
+        for task in snapshot.tasks.iter_mut() {
+            let was_done = self.tasks.iter().any(|previous_task| {
+                previous_task.uuid == task.uuid && previous_task.status.is_done()
+            });
+            match (was_done, task.status.is_done()) {
+                (false, true) => {
+                    let completed_at = Utc::now();
+                    task.completed_at = Some(completed_at);
+                    if task.recurrence.is_some() {
+                        task.completion_history.push(completed_at);
+                    }
+                    completed_task_uuids.push(task.uuid);
+                }
+                // Taking back a completion also takes it out of the history
+                (true, false) => {
+                    if let Some(completed_at) = task.completed_at.take() {
+                        task.completion_history
+                            .retain(|history_completed_at| *history_completed_at != completed_at);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.commit(snapshot)?;
+        Ok(completed_task_uuids)
+    }
+
+    pub fn set_tasks_status(
+        &mut self,
+        task_uuids: &[Uuid],
+        new_task_status: TaskStatus,
+    ) -> Result<Vec<Uuid>, SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        for task in new_snapshot.tasks.iter_mut() {
+            if task_uuids.contains(&task.uuid) {
+                task.status = new_task_status.clone();
+            }
+        }
+
+        self.commit_status_change(new_snapshot)
+    }
+
+    /// Moves each task to the next or previous status of its own
+    pub fn shift_tasks_status(
+        &mut self,
+        task_uuids: &[Uuid],
+        forward: bool,
+    ) -> Result<Vec<Uuid>, SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        for task in new_snapshot.tasks.iter_mut() {
+            if task_uuids.contains(&task.uuid) {
+                task.status = if forward {
+                    task.status.next()
+                } else {
+                    task.status.previous()
+                };
+            }
+        }
+
+        self.commit_status_change(new_snapshot)
+    }
+
+    pub fn set_tasks_recurrence(
+        &mut self,
+        task_uuids: &[Uuid],
+        recurrence: Option<Recurrence>,
+    ) -> Result<(), SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        for task in new_snapshot.tasks.iter_mut() {
+            if task_uuids.contains(&task.uuid) {
+                task.recurrence = recurrence.clone();
+            }
+        }
+
+        self.commit(new_snapshot)
+    }
+
+    /// Brings back the recurring tasks whose next occurrence is due, keeping their completion
+    /// history, without making it a change to undo
+    pub fn reset_recurring_tasks(&mut self) -> Result<(), SaveTaskError> {
+        let today = Local::now().date_naive();
+        if !self.tasks.iter().any(|task| task.is_due_again(today)) {
+            return Ok(());
+        }
+
+        let mut new_snapshot = self.snapshot();
+        for task in new_snapshot.tasks.iter_mut() {
+            if task.is_due_again(today) {
+                task.status = TaskStatus::initial();
+                task.completed_at = None;
+            }
+        }
+        self.stamp_changes(&mut new_snapshot);
+        if self.save_snapshot(new_snapshot)? {
+            self.clear_history();
+        }
+        Ok(())
+    }
+
+    pub fn tag_tasks(&mut self, task_uuids: &[Uuid], tag: &str) -> Result<(), SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        for task in new_snapshot.tasks.iter_mut() {
+            if task_uuids.contains(&task.uuid) && !task.tags.iter().any(|task_tag| task_tag == tag)
+            {
+                task.tags.push(tag.to_string());
+            }
+        }
+
+        self.commit(new_snapshot)
+    }
+
+    pub fn edit_task(
+        &mut self,
+        task_uuid: Uuid,
+        new_task_title: String,
+        new_task_status: TaskStatus,
+    ) -> Result<Vec<Uuid>, SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        for task in new_snapshot.tasks.iter_mut() {
+            if task.uuid == task_uuid {
+                task.title = new_task_title;
+                task.status = new_task_status;
+                break;
+            }
+        }
+
+        self.commit_status_change(new_snapshot)
+    }
+
+    pub fn move_tasks_to_list(
+        &mut self,
+        task_uuids: &[Uuid],
+        list: Option<String>,
+    ) -> Result<(), SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        for task in new_snapshot.tasks.iter_mut() {
+            if task_uuids.contains(&task.uuid) {
+                task.list = list.clone();
+            }
+        }
+
+        self.commit(new_snapshot)
+    }
+
+    pub fn set_task_notes(&mut self, task_uuid: Uuid, notes: String) -> Result<(), SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        if let Some(task) = new_snapshot
+            .tasks
+            .iter_mut()
+            .find(|task| task.uuid == task_uuid)
+        {
+            task.notes = notes;
+        }
+
+        self.commit(new_snapshot)
+    }
+
+    /// Makes a task blocked by the given tasks only, none leaving it free to work on
+    pub fn set_task_blockers(
+        &mut self,
+        task_uuid: Uuid,
+        blocker_uuids: &[Uuid],
+    ) -> Result<(), SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        if let Some(task) = new_snapshot
+            .tasks
+            .iter_mut()
+            .find(|task| task.uuid == task_uuid)
+        {
+            task.blocked_by = blocker_uuids.to_vec();
+        }
+
+        self.commit(new_snapshot)
+    }
+
+    /// Tasks blocking the given one that are not done yet, the task being unblocked once
+    /// there are none left
+    pub fn open_blockers(&self, task: &Task) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|blocker| task.blocked_by.contains(&blocker.uuid) && !blocker.status.is_done())
+            .collect()
+    }
+
+    /// Swaps the positions of two tasks in the manual order
+    pub fn swap_tasks(&mut self, first_uuid: Uuid, second_uuid: Uuid) -> Result<(), SaveTaskError> {
+        let position = |task_uuid: Uuid| {
+            self.tasks
+                .iter()
+                .position(|task_element| task_element.uuid == task_uuid)
+        };
+        if let (Some(first_idx), Some(second_idx)) = (position(first_uuid), position(second_uuid)) {
+            let mut new_snapshot = self.snapshot();
+            new_snapshot.tasks.swap(first_idx, second_idx);
+            self.commit(new_snapshot)?;
+        }
+        Ok(())
+    }
+
+    /// Moves the given tasks out of the main list into the archive file
+    pub fn archive_tasks(&mut self, task_uuids: &[Uuid]) -> Result<(), SaveTaskError> {
+        let (to_archive, new_tasks): (Vec<Task>, Vec<Task>) = self
+            .tasks
+            .iter()
+            .cloned()
+            .partition(|task_element| task_uuids.contains(&task_element.uuid));
+        let mut new_archived_tasks: Vec<Task> = self.archived_tasks.clone();
+        new_archived_tasks.extend(to_archive);
+
+        self.commit(TaskSnapshot {
+            tasks: new_tasks,
+            archived_tasks: new_archived_tasks,
+        })
+    }
+
+    /// Archives the done tasks of the selected list
+    pub fn archive_done_tasks(&mut self) -> Result<(), SaveTaskError> {
+        let done_task_uuids: Vec<Uuid> = self
+            .tasks
+            .iter()
+            .filter(|task_element| {
+                task_element.status.is_done() && task_element.list == self.selected_list
+            })
+            .map(|task_element| task_element.uuid)
+            .collect();
+        self.archive_tasks(&done_task_uuids)
+    }
+
+    /// Moves an archived task back to the end of the main list, returning its new index
+    pub fn restore_archived_task(&mut self, task_uuid: Uuid) -> Result<usize, SaveTaskError> {
+        let mut new_snapshot = self.snapshot();
+        if let Some(archived_idx) = new_snapshot
+            .archived_tasks
+            .iter()
+            .position(|task_element| task_element.uuid == task_uuid)
+        {
+            let restored_task = new_snapshot.archived_tasks.remove(archived_idx);
+            new_snapshot.tasks.push(restored_task);
+            self.commit(new_snapshot)?;
+        }
+        Ok(self.tasks.len().saturating_sub(1))
+    }
+}
+
+/// Finds tasks blocking each other in a loop through a blocker that none of the previous tasks
+/// had, returned in the order they block one another
+///
+/// Loops that were already there are left alone, so that they can still be broken by removing
+/// one of their blockers.
+fn dependency_cycle<'a>(tasks: &'a [Task], previous_tasks: &[Task]) -> Option<Vec<&'a Task>> {
+    let tasks_by_uuid: HashMap<Uuid, &Task> = tasks.iter().map(|task| (task.uuid, task)).collect();
+    let previous_blockers: HashMap<Uuid, &[Uuid]> = previous_tasks
+        .iter()
+        .map(|task| (task.uuid, task.blocked_by.as_slice()))
+        .collect();
+
+    for task in tasks {
+        let new_blocker_uuids = task.blocked_by.iter().filter(|blocker_uuid| {
+            !previous_blockers
+                .get(&task.uuid)
+                .is_some_and(|previous_blocker_uuids| previous_blocker_uuids.contains(blocker_uuid))
+        });
+        for blocker_uuid in new_blocker_uuids {
+            if *blocker_uuid == task.uuid {
+                return Some(vec![task]);
+            }
+            // Follows the blockers of the new one, looking for the way back to the task
+            let mut reached_from: HashMap<Uuid, Uuid> = HashMap::from([(*blocker_uuid, task.uuid)]);
+            let mut pending: Vec<Uuid> = vec![*blocker_uuid];
+            while let Some(current_uuid) = pending.pop() {
+                let Some(current_task) = tasks_by_uuid.get(&current_uuid) else {
+                    continue;
+                };
+                if current_task.blocked_by.contains(&task.uuid) {
+                    let mut cycle: Vec<&Task> = vec![current_task];
+                    let mut cycle_uuid = current_uuid;
+                    while let Some(previous_uuid) = reached_from.get(&cycle_uuid) {
+                        cycle_uuid = *previous_uuid;
+                        cycle.extend(tasks_by_uuid.get(&cycle_uuid));
+                        if cycle_uuid == task.uuid {
+                            break;
+                        }
+                    }
+                    cycle.reverse();
+                    return Some(cycle);
+                }
+                for next_uuid in &current_task.blocked_by {
+                    if !reached_from.contains_key(next_uuid) {
+                        reached_from.insert(*next_uuid, current_uuid);
+                        pending.push(*next_uuid);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A task of the default list with the given title, for the tests of the stores
+#[cfg(test)]
+pub(crate) fn test_task(title: &str) -> Task {
+    Task {
+        uuid: Uuid::new_v4(),
+        title: title.to_string(),
+        status: TaskStatus::initial(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::memory::MemoryTaskStore;
+
+    fn manager(tasks: Vec<Task>) -> TaskManager {
+        TaskManager::with_store_or_empty(Box::new(MemoryTaskStore::new(TaskSnapshot {
+            tasks,
+            archived_tasks: Vec::new(),
+        })))
+    }
+
+    fn titles(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|task| task.title.as_str()).collect()
+    }
+
+    #[test]
+    fn undo_and_redo_go_through_the_history() {
+        let first_task = test_task("first");
+        let mut task_manager = manager(vec![first_task.clone()]);
+        assert!(task_manager.add_task(test_task("second")).is_ok());
+        assert!(task_manager.delete_tasks(&[first_task.uuid]).is_ok());
+        assert_eq!(titles(&task_manager.tasks), ["second"]);
+
+        assert!(matches!(task_manager.undo(), Ok(true)));
+        assert_eq!(titles(&task_manager.tasks), ["first", "second"]);
+        assert!(matches!(task_manager.undo(), Ok(true)));
+        assert_eq!(titles(&task_manager.tasks), ["first"]);
+        assert!(matches!(task_manager.undo(), Ok(false)));
+
+        assert!(matches!(task_manager.redo(), Ok(true)));
+        assert_eq!(titles(&task_manager.tasks), ["first", "second"]);
+        assert!(task_manager.add_task(test_task("third")).is_ok());
+        assert!(matches!(task_manager.redo(), Ok(false)));
+    }
+
+    #[test]
+    fn changes_touching_one_task_are_told_apart() {
+        let (first_task, second_task) = (test_task("first"), test_task("second"));
+        let previous_tasks = vec![first_task.clone(), second_task.clone()];
+        let between = |tasks: Vec<Task>, archived_tasks: Vec<Task>| {
+            let next = TaskSnapshot {
+                tasks,
+                archived_tasks,
+            };
+            match TaskChange::between(&previous_tasks, &[], &next) {
+                Some(TaskChange::Added(task)) => format!("added {}", task.title),
+                Some(TaskChange::Updated(task)) => format!("updated {}", task.title),
+                Some(TaskChange::Deleted(task_uuid)) => format!("deleted {}", task_uuid),
+                None => String::from("none"),
+            }
+        };
+        let renamed_task = Task {
+            title: String::from("renamed"),
+            ..second_task.clone()
+        };
+
+        assert_eq!(
+            between(
+                vec![first_task.clone(), second_task.clone(), test_task("third")],
+                Vec::new()
+            ),
+            "added third"
+        );
+        assert_eq!(
+            between(vec![first_task.clone(), renamed_task.clone()], Vec::new()),
+            "updated renamed"
+        );
+        assert_eq!(
+            between(vec![second_task.clone()], Vec::new()),
+            format!("deleted {}", first_task.uuid)
+        );
+        assert_eq!(
+            between(vec![second_task.clone(), first_task.clone()], Vec::new()),
+            "none"
+        );
+        assert_eq!(
+            between(vec![second_task.clone()], vec![first_task.clone()]),
+            "none"
+        );
+    }
+
+    #[test]
+    fn dependency_cycles_are_found_in_blocking_order() {
+        let (mut first_task, mut second_task, mut third_task) =
+            (test_task("first"), test_task("second"), test_task("third"));
+        first_task.blocked_by = vec![second_task.uuid];
+        second_task.blocked_by = vec![third_task.uuid];
+        let tasks = vec![first_task.clone(), second_task.clone(), third_task.clone()];
+        assert!(dependency_cycle(&tasks, &[]).is_none());
+
+        third_task.blocked_by = vec![first_task.uuid];
+        let tasks = vec![first_task, second_task, third_task];
+        let cycle_titles: Vec<&str> = dependency_cycle(&tasks, &[])
+            .unwrap_or_default()
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect();
+        assert_eq!(cycle_titles, ["first", "second", "third"]);
+    }
+
+    #[test]
+    fn blocking_tasks_in_a_loop_is_refused() {
+        let (mut first_task, second_task) = (test_task("first"), test_task("second"));
+        first_task.blocked_by = vec![second_task.uuid];
+        let mut task_manager = manager(vec![first_task.clone(), second_task.clone()]);
+
+        let block_result = task_manager.set_task_blockers(second_task.uuid, &[first_task.uuid]);
+        assert!(matches!(
+            block_result,
+            Err(SaveTaskError::DependencyCycle(task_titles)) if task_titles.len() == 2
+        ));
+        assert!(task_manager.tasks[1].blocked_by.is_empty());
+        assert!(task_manager.set_task_blockers(first_task.uuid, &[]).is_ok());
+        assert!(
+            task_manager
+                .set_task_blockers(second_task.uuid, &[first_task.uuid])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn changes_that_change_nothing_are_not_undoable() {
+        let first_task = test_task("first");
+        let mut task_manager = manager(vec![first_task.clone()]);
+        assert!(task_manager.delete_tasks(&[]).is_ok());
+        assert!(
+            task_manager
+                .set_tasks_status(&[first_task.uuid], TaskStatus::initial())
+                .is_ok()
+        );
+
+        assert!(matches!(task_manager.undo(), Ok(false)));
+        assert!(task_manager.tasks[0] == first_task);
+    }
+
+    #[test]
+    fn loops_already_stored_do_not_block_other_changes() {
+        let (mut first_task, mut second_task) = (test_task("first"), test_task("second"));
+        first_task.blocked_by = vec![second_task.uuid];
+        second_task.blocked_by = vec![first_task.uuid];
+        let mut task_manager = manager(vec![first_task.clone(), second_task]);
+        assert!(task_manager.dependency_cycle_warning().is_some());
+
+        assert!(task_manager.add_task(test_task("third")).is_ok());
+        assert!(task_manager.set_task_blockers(first_task.uuid, &[]).is_ok());
+        assert!(task_manager.dependency_cycle_warning().is_none());
+    }
+
+    #[test]
+    fn loops_made_by_merging_two_changes_are_refused() {
+        let (first_task, second_task) = (test_task("first"), test_task("second"));
+        let store = MemoryTaskStore::new(TaskSnapshot {
+            tasks: vec![first_task.clone(), second_task.clone()],
+            archived_tasks: Vec::new(),
+        });
+        let mut other_task_manager = TaskManager::with_store_or_empty(Box::new(store.share()));
+        let mut task_manager = TaskManager::with_store_or_empty(Box::new(store));
+
+        assert!(
+            other_task_manager
+                .set_task_blockers(first_task.uuid, &[second_task.uuid])
+                .is_ok()
+        );
+        assert!(matches!(
+            task_manager.set_task_blockers(second_task.uuid, &[first_task.uuid]),
+            Err(SaveTaskError::DependencyCycle(_))
+        ));
+    }
+
+    #[test]
+    fn tasks_are_unblocked_once_their_blockers_are_done() {
+        let (mut first_task, second_task) = (test_task("first"), test_task("second"));
+        first_task.blocked_by = vec![second_task.uuid];
+        let mut task_manager = manager(vec![first_task, second_task.clone()]);
+        let blocker_uuids: Vec<Uuid> = task_manager
+            .open_blockers(&task_manager.tasks[0])
+            .iter()
+            .map(|blocker| blocker.uuid)
+            .collect();
+        assert_eq!(blocker_uuids, [second_task.uuid]);
+
+        assert!(
+            task_manager
+                .set_tasks_status(&[second_task.uuid], TaskStatus::done())
+                .is_ok()
+        );
+        assert!(
+            task_manager
+                .open_blockers(&task_manager.tasks[0])
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn importing_a_file_again_adds_nothing() {
+        let content = "Buy milk +home\n(A) 2024-01-01 Call Bob\n";
+        let mut task_manager = manager(Vec::new());
+        assert!(matches!(
+            task_manager.import_tasks(crate::formats::todo_txt::parse(content)),
+            Ok(2)
+        ));
+        assert!(matches!(
+            task_manager.import_tasks(crate::formats::todo_txt::parse(content)),
+            Ok(0)
+        ));
+        assert_eq!(titles(&task_manager.tasks), ["Buy milk", "Call Bob"]);
+    }
+}
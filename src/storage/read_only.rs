@@ -0,0 +1,26 @@
+use crate::storage::{LoadTaskFileError, SaveTaskError, TaskSnapshot, TaskStore};
+
+/// Loads and follows the tasks of another store but refuses every change
+pub struct ReadOnlyTaskStore {
+    store: Box<dyn TaskStore>,
+}
+
+impl ReadOnlyTaskStore {
+    pub fn new(store: Box<dyn TaskStore>) -> Self {
+        ReadOnlyTaskStore { store }
+    }
+}
+
+impl TaskStore for ReadOnlyTaskStore {
+    fn load(&mut self) -> Result<TaskSnapshot, LoadTaskFileError> {
+        self.store.load()
+    }
+
+    fn save(&mut self, _snapshot: &TaskSnapshot) -> Result<(), SaveTaskError> {
+        Err(SaveTaskError::ReadOnly)
+    }
+
+    fn has_changed(&mut self) -> bool {
+        self.store.has_changed()
+    }
+}
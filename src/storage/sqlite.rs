@@ -0,0 +1,361 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{Connection, Row, params, types::Type};
+use uuid::Uuid;
+
+use crate::{
+    popups::task_list::Task,
+    sessions::{FileSessionStore, Session, SessionFileError, SessionStore},
+    storage::{
+        LoadTaskFileError, SaveTaskError, TaskSnapshot, TaskStore, file::FileTaskStore,
+        migrations::migrate_tasks,
+    },
+};
+
+/// Tasks are kept whole as JSON in `data`, the other columns being derived from it for querying
+const SCHEMA: &str = "
+CREATE TABLE tasks (
+    uuid TEXT PRIMARY KEY,
+    archived INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    title TEXT GENERATED ALWAYS AS (json_extract(data, '$.title')) VIRTUAL,
+    status TEXT GENERATED ALWAYS AS (json_extract(data, '$.status')) VIRTUAL,
+    priority TEXT GENERATED ALWAYS AS (json_extract(data, '$.priority')) VIRTUAL,
+    created_at TEXT GENERATED ALWAYS AS (json_extract(data, '$.created_at')) VIRTUAL,
+    completed_at TEXT GENERATED ALWAYS AS (json_extract(data, '$.completed_at')) VIRTUAL,
+    list TEXT GENERATED ALWAYS AS (json_extract(data, '$.list')) VIRTUAL
+);
+CREATE INDEX tasks_by_position ON tasks (archived, position);
+CREATE INDEX tasks_by_status ON tasks (status);
+CREATE INDEX tasks_by_completed_at ON tasks (completed_at);
+CREATE INDEX tasks_by_list ON tasks (list);
+
+CREATE TABLE sessions (
+    id INTEGER PRIMARY KEY,
+    start TEXT NOT NULL,
+    end TEXT NOT NULL,
+    phase TEXT NOT NULL,
+    duration_seconds INTEGER NOT NULL,
+    task_uuid TEXT,
+    task_title TEXT
+);
+CREATE INDEX sessions_by_start ON sessions (start);
+CREATE INDEX sessions_by_task_uuid ON sessions (task_uuid);
+";
+
+/// Stored in `PRAGMA user_version`, 0 being a database that was just created
+const SCHEMA_VERSION: i32 = 2;
+
+/// Upgrades a database from each version to the next, `SCHEMA_UPGRADES[0]` upgrading version 1
+/// to 2, while `SCHEMA` creates the latest version directly
+const SCHEMA_UPGRADES: [&str; SCHEMA_VERSION as usize - 1] = ["
+ALTER TABLE tasks ADD COLUMN list TEXT GENERATED ALWAYS AS (json_extract(data, '$.list')) VIRTUAL;
+CREATE INDEX tasks_by_list ON tasks (list);
+"];
+
+#[derive(Debug)]
+pub enum OpenDatabaseError {
+    Database(PathBuf, rusqlite::Error),
+    TaskFile(LoadTaskFileError),
+    SessionFile(SessionFileError),
+}
+
+impl Display for OpenDatabaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenDatabaseError::Database(path, error) => write!(
+                f,
+                "Failed to open the database at {}: {}",
+                path.display(),
+                error
+            ),
+            OpenDatabaseError::TaskFile(error) => {
+                write!(f, "Failed to import the tasks into the database: {}", error)
+            }
+            OpenDatabaseError::SessionFile(error) => write!(
+                f,
+                "Failed to import the session history into the database: {}",
+                error
+            ),
+        }
+    }
+}
+
+/// Opens the database, creating its tables and importing the JSON files the first time, or
+/// upgrading the tables of an older version
+pub fn open_database(
+    database_path: &Path,
+    tasks_file_path: &Path,
+    sessions_file_path: &Path,
+) -> Result<Rc<Connection>, OpenDatabaseError> {
+    let database_error = |error| OpenDatabaseError::Database(database_path.to_path_buf(), error);
+
+    let mut connection = Connection::open(database_path).map_err(database_error)?;
+    let schema_version: i32 = connection
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(database_error)?;
+
+    if schema_version == 0 {
+        let snapshot = FileTaskStore::new(tasks_file_path.to_path_buf())
+            .load()
+            .map_err(OpenDatabaseError::TaskFile)?;
+        let sessions = FileSessionStore::new(sessions_file_path.to_path_buf())
+            .load()
+            .map_err(OpenDatabaseError::SessionFile)?;
+
+        let transaction = connection.transaction().map_err(database_error)?;
+        transaction.execute_batch(SCHEMA).map_err(database_error)?;
+        insert_tasks(&transaction, &snapshot).map_err(database_error)?;
+        for session in &sessions {
+            insert_session(&transaction, session).map_err(database_error)?;
+        }
+        transaction
+            .pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(database_error)?;
+        transaction.commit().map_err(database_error)?;
+    } else if schema_version < SCHEMA_VERSION {
+        let transaction = connection.transaction().map_err(database_error)?;
+        for schema_upgrade in SCHEMA_UPGRADES.iter().skip(schema_version as usize - 1) {
+            transaction
+                .execute_batch(schema_upgrade)
+                .map_err(database_error)?;
+        }
+        transaction
+            .pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(database_error)?;
+        transaction.commit().map_err(database_error)?;
+    }
+
+    Ok(Rc::new(connection))
+}
+
+/// Saves each change to the single row it touches instead of rewriting every task
+pub struct SqliteTaskStore {
+    connection: Rc<Connection>,
+    database_path: PathBuf,
+    /// Counter SQLite increments whenever another connection commits to the database
+    synced_data_version: Option<i64>,
+}
+
+impl SqliteTaskStore {
+    pub fn new(connection: Rc<Connection>, database_path: PathBuf) -> Self {
+        SqliteTaskStore {
+            connection,
+            database_path,
+            synced_data_version: None,
+        }
+    }
+
+    /// Rows keep the fields of the version that wrote them, and as migrations only add the
+    /// missing fields, all of them are run on every row
+    fn select_tasks(&self, archived: bool) -> rusqlite::Result<Vec<Task>> {
+        self.connection
+            .prepare("SELECT data FROM tasks WHERE archived = ?1 ORDER BY position")?
+            .query_map([archived], |row| {
+                let mut task: serde_json::Value = serde_json::from_str(&row.get::<_, String>(0)?)
+                    .map_err(|error| conversion_error(0, error))?;
+                migrate_tasks(std::slice::from_mut(&mut task), 0);
+                serde_json::from_value(task).map_err(|error| conversion_error(0, error))
+            })?
+            .collect()
+    }
+
+    fn data_version(&self) -> Option<i64> {
+        self.connection
+            .pragma_query_value(None, "data_version", |row| row.get(0))
+            .ok()
+    }
+
+    fn save_error(&self, error: rusqlite::Error) -> SaveTaskError {
+        SaveTaskError::Database(self.database_path.clone(), error)
+    }
+}
+
+impl TaskStore for SqliteTaskStore {
+    fn load(&mut self) -> Result<TaskSnapshot, LoadTaskFileError> {
+        let load_error = |error| LoadTaskFileError::Database(self.database_path.clone(), error);
+        let data_version = self.data_version();
+        let snapshot = TaskSnapshot {
+            tasks: self.select_tasks(false).map_err(load_error)?,
+            archived_tasks: self.select_tasks(true).map_err(load_error)?,
+        };
+        self.synced_data_version = data_version;
+        Ok(snapshot)
+    }
+
+    /// Our own commits leave `data_version` as it is, so unlike files there is nothing to update
+    /// after saving
+    fn has_changed(&mut self) -> bool {
+        let data_version = self.data_version();
+        let has_changed = data_version != self.synced_data_version;
+        self.synced_data_version = data_version;
+        has_changed
+    }
+
+    fn save(&mut self, snapshot: &TaskSnapshot) -> Result<(), SaveTaskError> {
+        let transaction = self
+            .connection
+            .unchecked_transaction()
+            .map_err(|error| self.save_error(error))?;
+        transaction
+            .execute("DELETE FROM tasks", [])
+            .map_err(|error| self.save_error(error))?;
+        insert_tasks(&transaction, snapshot).map_err(|error| self.save_error(error))?;
+        transaction.commit().map_err(|error| self.save_error(error))
+    }
+
+    fn add_task(&mut self, task: &Task, _snapshot: &TaskSnapshot) -> Result<(), SaveTaskError> {
+        let insert_task = || {
+            self.connection.execute(
+                "INSERT INTO tasks (uuid, archived, position, data) VALUES (?1, FALSE,
+                    (SELECT COALESCE(MAX(position), -1) + 1 FROM tasks WHERE archived = FALSE), ?2)",
+                params![task.uuid.to_string(), task_json(task)?],
+            )
+        };
+        insert_task()
+            .map(|_| ())
+            .map_err(|error| self.save_error(error))
+    }
+
+    fn update_task(&mut self, task: &Task, _snapshot: &TaskSnapshot) -> Result<(), SaveTaskError> {
+        let update_task = || {
+            self.connection.execute(
+                "UPDATE tasks SET data = ?2 WHERE uuid = ?1",
+                params![task.uuid.to_string(), task_json(task)?],
+            )
+        };
+        update_task()
+            .map(|_| ())
+            .map_err(|error| self.save_error(error))
+    }
+
+    fn delete_task(
+        &mut self,
+        task_uuid: Uuid,
+        _snapshot: &TaskSnapshot,
+    ) -> Result<(), SaveTaskError> {
+        self.connection
+            .execute("DELETE FROM tasks WHERE uuid = ?1", [task_uuid.to_string()])
+            .map(|_| ())
+            .map_err(|error| self.save_error(error))
+    }
+}
+
+/// Appends each finished session as a row
+pub struct SqliteSessionStore {
+    connection: Rc<Connection>,
+    database_path: PathBuf,
+}
+
+impl SqliteSessionStore {
+    pub fn new(connection: Rc<Connection>, database_path: PathBuf) -> Self {
+        SqliteSessionStore {
+            connection,
+            database_path,
+        }
+    }
+}
+
+impl SessionStore for SqliteSessionStore {
+    fn load(&mut self) -> Result<Vec<Session>, SessionFileError> {
+        let select_sessions = || -> rusqlite::Result<Vec<Session>> {
+            self.connection
+                .prepare(
+                    "SELECT start, end, phase, duration_seconds, task_uuid, task_title
+                    FROM sessions ORDER BY id",
+                )?
+                .query_map([], session_from_row)?
+                .collect()
+        };
+        select_sessions()
+            .map_err(|error| SessionFileError::Database(self.database_path.clone(), error))
+    }
+
+    fn add_session(
+        &mut self,
+        session: &Session,
+        _sessions: &[Session],
+    ) -> Result<(), SessionFileError> {
+        insert_session(&self.connection, session)
+            .map_err(|error| SessionFileError::Database(self.database_path.clone(), error))
+    }
+}
+
+fn insert_tasks(connection: &Connection, snapshot: &TaskSnapshot) -> rusqlite::Result<()> {
+    let mut statement = connection
+        .prepare("INSERT INTO tasks (uuid, archived, position, data) VALUES (?1, ?2, ?3, ?4)")?;
+    for (archived, tasks) in [(false, &snapshot.tasks), (true, &snapshot.archived_tasks)] {
+        for (position, task) in tasks.iter().enumerate() {
+            statement.execute(params![
+                task.uuid.to_string(),
+                archived,
+                position as i64,
+                task_json(task)?
+            ])?;
+        }
+    }
+    Ok(())
+}
+
+fn task_json(task: &Task) -> rusqlite::Result<String> {
+    serde_json::to_string(task)
+        .map_err(|error| rusqlite::Error::ToSqlConversionFailure(Box::new(error)))
+}
+
+fn insert_session(connection: &Connection, session: &Session) -> rusqlite::Result<()> {
+    let phase = serde_json::to_value(session.phase)
+        .map_err(|error| rusqlite::Error::ToSqlConversionFailure(Box::new(error)))?;
+    connection.execute(
+        "INSERT INTO sessions (start, end, phase, duration_seconds, task_uuid, task_title)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            timestamp(&session.start),
+            timestamp(&session.end),
+            phase.as_str(),
+            session.duration_seconds as i64,
+            session.task_uuid.map(|task_uuid| task_uuid.to_string()),
+            session.task_title,
+        ],
+    )?;
+    Ok(())
+}
+
+fn session_from_row(row: &Row) -> rusqlite::Result<Session> {
+    let parse_timestamp = |idx: usize| -> rusqlite::Result<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&row.get::<_, String>(idx)?)
+            .map(|timestamp| timestamp.to_utc())
+            .map_err(|error| conversion_error(idx, error))
+    };
+    let task_uuid = row
+        .get::<_, Option<String>>(4)?
+        .map(|task_uuid| Uuid::parse_str(&task_uuid).map_err(|error| conversion_error(4, error)))
+        .transpose()?;
+
+    Ok(Session {
+        start: parse_timestamp(0)?,
+        end: parse_timestamp(1)?,
+        phase: serde_json::from_value(serde_json::Value::String(row.get(2)?))
+            .map_err(|error| conversion_error(2, error))?,
+        duration_seconds: row.get::<_, i64>(3)? as u64,
+        task_uuid,
+        task_title: row.get(5)?,
+    })
+}
+
+/// Same format as the JSON files, so that both sort and compare alike
+fn timestamp(date_time: &DateTime<Utc>) -> String {
+    date_time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+fn conversion_error(
+    idx: usize,
+    error: impl std::error::Error + Send + Sync + 'static,
+) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(error))
+}
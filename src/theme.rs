@@ -9,6 +9,7 @@ pub struct Theme {
     pub error_text_color: Color,
     pub work_accent_color: Color,
     pub break_accent_color: Color,
+    pub marked_text_color: Color,
 }
 
 impl Theme {
@@ -20,9 +21,11 @@ impl Theme {
             error_text_color: Color::Rgb(243, 139, 168),
             work_accent_color: Color::Rgb(166, 227, 161),
             break_accent_color: Color::Rgb(137, 180, 250),
+            marked_text_color: Color::Rgb(203, 166, 247),
         }
     }
 
+    #[allow(dead_code)]
     pub fn catppuccin_latte() -> Self {
         Self {
             background_style: Style::default().bg(Color::Rgb(239, 241, 245)),
@@ -31,6 +34,7 @@ impl Theme {
             error_text_color: Color::Rgb(210, 15, 57),
             work_accent_color: Color::Rgb(64, 160, 43),
             break_accent_color: Color::Rgb(30, 102, 245),
+            marked_text_color: Color::Rgb(136, 57, 239),
         }
     }
 
@@ -54,6 +58,10 @@ impl Theme {
         Style::default().fg(self.text_color)
     }
 
+    pub fn marked_text_style(&self) -> Style {
+        Style::default().fg(self.marked_text_color)
+    }
+
     pub fn error_text_style(&self) -> Style {
         Style::default().fg(self.error_text_color)
     }
@@ -1,8 +1,11 @@
+use chrono::TimeDelta;
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use serde::{Deserialize, Serialize};
 
 pub const DEFAULT_WORK_DURATION_MINUTES: u64 = 45;
 pub const DEFAULT_BREAK_DURATION_MINUTES: u64 = 10;
 
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum CountdownType {
     Work,
     Break,
@@ -16,3 +19,16 @@ pub fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
     let [area] = horizontal.areas(area);
     area
 }
+
+/// Writes a duration with its two largest units, such as `2d 3h` or `5m`
+pub fn format_duration(duration: TimeDelta) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
@@ -0,0 +1,120 @@
+use std::{collections::HashSet, fmt::Display, sync::OnceLock};
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+/// One step of the workflow, as configured in the settings file
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StatusDefinition {
+    pub name: String,
+    pub glyph: String,
+    /// Color of the glyph, a name such as `red` or a hex code such as `#f38ba8`
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Whether tasks with this status count as done, to archive them or credit a streak
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl StatusDefinition {
+    fn new(name: &str, glyph: &str, done: bool) -> Self {
+        StatusDefinition {
+            name: name.to_string(),
+            glyph: glyph.to_string(),
+            color: None,
+            done,
+        }
+    }
+
+    pub fn color(&self) -> Option<Color> {
+        self.color.as_deref()?.parse().ok()
+    }
+}
+
+/// Statuses a task moves through, in order, the first one being given to new tasks
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Workflow {
+    pub statuses: Vec<StatusDefinition>,
+}
+
+impl Default for Workflow {
+    fn default() -> Self {
+        Workflow {
+            statuses: vec![
+                StatusDefinition::new("Todo", "❌", false),
+                StatusDefinition::new("Ongoing", "⏳", false),
+                StatusDefinition::new("Done", "✅", true),
+            ],
+        }
+    }
+}
+
+/// Why the workflow of the settings file cannot be used
+pub enum WorkflowError {
+    NoStatus,
+    NoDoneStatus,
+    EmptyName,
+    DuplicateName(String),
+}
+
+impl Display for WorkflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            WorkflowError::NoStatus => String::from("it has no status"),
+            WorkflowError::NoDoneStatus => String::from("none of its statuses is marked done"),
+            WorkflowError::EmptyName => String::from("one of its statuses has no name"),
+            WorkflowError::DuplicateName(name) => {
+                format!("the status '{}' is defined more than once", name)
+            }
+        };
+        write!(
+            f,
+            "The workflow of the settings file is not used, the default one is instead: {}",
+            reason
+        )
+    }
+}
+
+impl Workflow {
+    /// A workflow needs a status to start from, one to finish with and no name used twice
+    fn validate(&self) -> Result<(), WorkflowError> {
+        if self.statuses.is_empty() {
+            return Err(WorkflowError::NoStatus);
+        }
+        if !self.statuses.iter().any(|status| status.done) {
+            return Err(WorkflowError::NoDoneStatus);
+        }
+        let mut names: HashSet<&str> = HashSet::new();
+        for status in &self.statuses {
+            if status.name.trim().is_empty() {
+                return Err(WorkflowError::EmptyName);
+            }
+            if !names.insert(&status.name) {
+                return Err(WorkflowError::DuplicateName(status.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn status(&self, name: &str) -> Option<&StatusDefinition> {
+        self.statuses.iter().find(|status| status.name == name)
+    }
+
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.statuses.iter().position(|status| status.name == name)
+    }
+}
+
+static WORKFLOW: OnceLock<Workflow> = OnceLock::new();
+
+/// Sets the workflow read from the settings for the rest of the run, keeping the default one
+/// when it is not usable
+pub fn set_workflow(workflow: Workflow) -> Result<(), WorkflowError> {
+    workflow.validate()?;
+    let _ = WORKFLOW.set(workflow);
+    Ok(())
+}
+
+pub fn workflow() -> &'static Workflow {
+    WORKFLOW.get_or_init(Workflow::default)
+}